@@ -0,0 +1,236 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use std::collections::{HashMap, HashSet};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    braced, Attribute, Data, DeriveInput, Error, FnArg, GenericParam, Generics, Ident, Pat, Path,
+    Result, Signature, TraitItemFn, Type,
+};
+
+/// `RangedWeapon { fn reload(&mut self) -> Result<(), WeaponError>; ... }`
+struct TargetTrait {
+    path: Path,
+    methods: Vec<Signature>,
+}
+
+impl Parse for TargetTrait {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let content;
+        braced!(content in input);
+
+        let mut methods = Vec::new();
+        while !content.is_empty() {
+            let method: TraitItemFn = content.parse()?;
+            if let Some(body) = &method.default {
+                return Err(Error::new_spanned(body, "expected a method signature ending with `;`"));
+            }
+            methods.push(method.sig);
+        }
+        Ok(Self { path, methods })
+    }
+}
+
+struct Adaptee<'a> {
+    variant: &'a Ident,
+    ty: &'a Type,
+    /// Adaptee method called for each target method, when named differently.
+    methods: HashMap<String, Ident>,
+    wrapper: Option<Ident>,
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return Err(Error::new_spanned(input, "Adapter can only be derived for enums")),
+    };
+    if variants.is_empty() {
+        return Err(Error::new_spanned(input, "Adapter requires at least one variant"));
+    }
+
+    let target = parse_target_trait(input)?;
+    let adaptees = variants
+        .iter()
+        .map(|variant| {
+            let ty = match &variant.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+                _ => return Err(Error::new_spanned(variant, "Adapter requires variants holding exactly one adaptee, like `Bow(Bow)`")),
+            };
+            let (methods, wrapper) = parse_variant_options(&variant.attrs, &target)?;
+            Ok(Adaptee { variant: &variant.ident, ty, methods, wrapper })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let arguments = target
+        .methods
+        .iter()
+        .map(method_arguments)
+        .collect::<Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let trait_path = &target.path;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Mixed-site, so a method parameter named `adaptee` is not shadowed by the binding.
+    let binding = Ident::new("__adaptee", Span::mixed_site());
+    let enum_methods = target.methods.iter().zip(&arguments).map(|(signature, arguments)| {
+        let arms = adaptees.iter().map(|adaptee| {
+            let variant = adaptee.variant;
+            let method = adaptee_method(adaptee, signature);
+            quote!(#name::#variant(#binding) => #binding.#method(#(#arguments),*))
+        });
+        quote! {
+            #signature {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    });
+
+    let wrappers = adaptees.iter().filter_map(|adaptee| {
+        let wrapper = adaptee.wrapper.as_ref()?;
+        let ty = adaptee.ty;
+        let generics = generics_used_by(&input.generics, ty);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let vis = &input.vis;
+        let doc = format!("Adapts the adaptee of [`{}::{}`] on its own.", name, adaptee.variant);
+        let methods = target.methods.iter().zip(&arguments).map(|(signature, arguments)| {
+            let method = adaptee_method(adaptee, signature);
+            quote! {
+                #signature {
+                    self.0.#method(#(#arguments),*)
+                }
+            }
+        });
+        Some(quote! {
+            #[doc = #doc]
+            #vis struct #wrapper #generics (pub #ty) #where_clause;
+
+            impl #impl_generics #trait_path for #wrapper #ty_generics #where_clause {
+                #(#methods)*
+            }
+        })
+    });
+
+    Ok(quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            #(#enum_methods)*
+        }
+
+        #(#wrappers)*
+    })
+}
+
+fn parse_target_trait(input: &DeriveInput) -> Result<TargetTrait> {
+    let mut target = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("adapter")) {
+        if target.is_some() {
+            return Err(Error::new_spanned(attr, "only one target trait can be adapted to"));
+        }
+        target = Some(attr.parse_args::<TargetTrait>()?);
+    }
+
+    target.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "Adapter requires the target trait, like `#[adapter(RangedWeapon { fn shoot(&mut self) -> bool; })]`",
+        )
+    })
+}
+
+fn parse_variant_options(attrs: &[Attribute], target: &TargetTrait) -> Result<(HashMap<String, Ident>, Option<Ident>)> {
+    let mut methods = HashMap::new();
+    let mut wrapper = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("adapter")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("wrapper") {
+                wrapper = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+
+            let method = match meta.path.get_ident() {
+                Some(method) if target.methods.iter().any(|signature| signature.ident == *method) => method.to_string(),
+                _ => return Err(meta.error("expected `wrapper` or a method of the target trait")),
+            };
+            methods.insert(method, meta.value()?.parse()?);
+            Ok(())
+        })?;
+    }
+
+    Ok((methods, wrapper))
+}
+
+/// The arguments to forward, that is every input but the receiver.
+fn method_arguments(signature: &Signature) -> Result<Vec<Ident>> {
+    let mut inputs = signature.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(_)) => {}
+        _ => return Err(Error::new_spanned(signature, "adapted methods must take `self`, `&self` or `&mut self`")),
+    }
+
+    inputs
+        .map(|input| match input {
+            FnArg::Typed(typed) => match &*typed.pat {
+                Pat::Ident(pat) => Ok(pat.ident.clone()),
+                pat => Err(Error::new_spanned(pat, "adapted methods must name their arguments")),
+            },
+            FnArg::Receiver(receiver) => Err(Error::new_spanned(receiver, "unexpected receiver")),
+        })
+        .collect()
+}
+
+fn adaptee_method<'a>(adaptee: &'a Adaptee, signature: &'a Signature) -> &'a Ident {
+    adaptee.methods.get(&signature.ident.to_string()).unwrap_or(&signature.ident)
+}
+
+/// The generic parameters of the enum that `ty` mentions, so a wrapper holding
+/// only `ty` has no unused parameter, along with the `where` predicates on them.
+fn generics_used_by(generics: &Generics, ty: &Type) -> Generics {
+    fn collect_idents(tokens: TokenStream, idents: &mut HashSet<String>) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => {
+                    idents.insert(ident.to_string());
+                }
+                TokenTree::Group(group) => collect_idents(group.stream(), idents),
+                _ => {}
+            }
+        }
+    }
+
+    fn param_ident(param: &GenericParam) -> String {
+        match param {
+            GenericParam::Lifetime(param) => param.lifetime.ident.to_string(),
+            GenericParam::Type(param) => param.ident.to_string(),
+            GenericParam::Const(param) => param.ident.to_string(),
+        }
+    }
+
+    let mut idents = HashSet::new();
+    collect_idents(quote!(#ty), &mut idents);
+
+    let (used, unused): (Vec<&GenericParam>, Vec<&GenericParam>) =
+        generics.params.iter().partition(|param| idents.contains(&param_ident(param)));
+    let unused: HashSet<String> = unused.into_iter().map(param_ident).collect();
+    let params: Punctuated<GenericParam, syn::Token![,]> = used.into_iter().cloned().collect();
+
+    // Predicates mentioning a parameter the wrapper doesn't have are left out.
+    let where_clause = generics.where_clause.as_ref().map(|where_clause| {
+        let mut where_clause = where_clause.clone();
+        where_clause.predicates = where_clause
+            .predicates
+            .into_iter()
+            .filter(|predicate| {
+                let mut mentioned = HashSet::new();
+                collect_idents(quote!(#predicate), &mut mentioned);
+                mentioned.is_disjoint(&unused)
+            })
+            .collect();
+        where_clause
+    });
+
+    Generics { params, where_clause, ..Generics::default() }
+}
@@ -0,0 +1,320 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, Fields, GenericArgument, Ident, PathArguments,
+    Result, Type,
+};
+
+#[derive(Default)]
+struct StructOptions {
+    error: Option<Ident>,
+    name: Option<Ident>,
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    required: bool,
+    default: Option<Option<Expr>>,
+    each: Option<Ident>,
+    error: Option<Ident>,
+}
+
+enum Missing {
+    Error(Ident),
+    Default(TokenStream),
+}
+
+struct BuilderField<'a> {
+    ident: &'a Ident,
+    /// Type taken by the setter and held by the builder inside an `Option`.
+    value_ty: &'a Type,
+    /// Whether the built struct stores the value wrapped in `Some`.
+    wraps_option: bool,
+    missing: Missing,
+    each: Option<(Ident, &'a Type)>,
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(input, "Builder requires a struct with named fields")),
+        },
+        _ => return Err(Error::new_spanned(input, "Builder can only be derived for structs")),
+    };
+
+    let options = parse_struct_options(&input.attrs)?;
+    let target = &input.ident;
+    let vis = &input.vis;
+    let builder = options.name.unwrap_or_else(|| format_ident!("{}Builder", target));
+    let error = options.error.clone().unwrap_or_else(|| format_ident!("{}BuildingError", target));
+
+    let fields = fields
+        .iter()
+        .map(|field| builder_field(field.ident.as_ref().unwrap(), &field.ty, &field.attrs))
+        .collect::<Result<Vec<_>>>()?;
+
+    let error_enum = if options.error.is_none() {
+        let variants = fields.iter().filter_map(|field| match &field.missing {
+            Missing::Error(variant) => Some(variant),
+            Missing::Default(_) => None,
+        });
+        let doc = format!("Errors reported by [`{}`] for fields that were never set.", builder);
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, PartialEq)]
+            #vis enum #error {
+                #(#variants,)*
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let builder_fields = fields.iter().map(|field| {
+        let ident = field.ident;
+        let value_ty = field.value_ty;
+        quote!(#ident: ::core::option::Option<#value_ty>)
+    });
+    let field_idents: Vec<_> = fields.iter().map(|field| field.ident).collect();
+    let setters = fields.iter().map(|field| setters(field, vis));
+
+    let bindings = fields.iter().map(|field| {
+        let ident = field.ident;
+        match &field.missing {
+            Missing::Error(variant) => quote! {
+                let #ident = self.#ident;
+                if #ident.is_none() {
+                    __errors.push(#error::#variant);
+                }
+            },
+            Missing::Default(default) => {
+                let value = if field.wraps_option { quote!(::core::option::Option::Some(value)) } else { quote!(value) };
+                quote! {
+                    let #ident = match self.#ident {
+                        ::core::option::Option::Some(value) => #value,
+                        ::core::option::Option::None => #default,
+                    };
+                }
+            }
+        }
+    });
+
+    let required: Vec<_> = fields
+        .iter()
+        .filter(|field| matches!(field.missing, Missing::Error(_)))
+        .map(|field| field.ident)
+        .collect();
+    let initializers = fields.iter().map(|field| {
+        let ident = field.ident;
+        match (&field.missing, field.wraps_option) {
+            (Missing::Error(_), true) => quote!(#ident: ::core::option::Option::Some(#ident)),
+            _ => quote!(#ident),
+        }
+    });
+    let built = quote!(::core::result::Result::Ok(#target { #(#initializers,)* }));
+    let body = if required.is_empty() {
+        quote! {
+            #(#bindings)*
+            #built
+        }
+    } else {
+        quote! {
+            let mut __errors = ::std::vec::Vec::new();
+            #(#bindings)*
+            match (#(#required,)*) {
+                (#(::core::option::Option::Some(#required),)*) => #built,
+                _ => ::core::result::Result::Err(__errors),
+            }
+        }
+    };
+
+    let builder_doc = format!("Builder for [`{}`].", target);
+
+    Ok(quote! {
+        #error_enum
+
+        #[doc = #builder_doc]
+        #vis struct #builder #impl_generics #where_clause {
+            #(#builder_fields,)*
+        }
+
+        impl #impl_generics ::core::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#field_idents: ::core::option::Option::None,)*
+                }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #vis fn new() -> Self {
+                ::core::default::Default::default()
+            }
+
+            #(#setters)*
+        }
+
+        impl #impl_generics ::design_patterns_rs::builder::Builder<#target #ty_generics, ::std::vec::Vec<#error>>
+            for #builder #ty_generics #where_clause
+        {
+            fn build(self) -> ::core::result::Result<#target #ty_generics, ::std::vec::Vec<#error>> {
+                #body
+            }
+        }
+    })
+}
+
+fn setters(field: &BuilderField, vis: &syn::Visibility) -> TokenStream {
+    let ident = field.ident;
+    let value_ty = field.value_ty;
+    let with = format_ident!("with_{}", ident);
+
+    let accumulator = match &field.each {
+        Some((each, item_ty)) => {
+            let with_each = format_ident!("with_{}", each);
+            quote! {
+                #vis fn #with_each(&mut self, #each: #item_ty) -> &mut Self {
+                    self.#ident.get_or_insert_with(::std::vec::Vec::new).push(#each);
+                    self
+                }
+            }
+        }
+        None => TokenStream::new(),
+    };
+
+    let collides = matches!(&field.each, Some((each, _)) if each == ident);
+    if collides {
+        return accumulator;
+    }
+
+    quote! {
+        #vis fn #with(&mut self, #ident: #value_ty) -> &mut Self {
+            self.#ident = ::core::option::Option::Some(#ident);
+            self
+        }
+
+        #accumulator
+    }
+}
+
+fn builder_field<'a>(ident: &'a Ident, ty: &'a Type, attrs: &[Attribute]) -> Result<BuilderField<'a>> {
+    let options = parse_field_options(attrs)?;
+
+    if options.required && options.default.is_some() {
+        return Err(Error::new_spanned(ident, "a field cannot be both `required` and `default`"));
+    }
+
+    let option_inner = generic_argument_of(ty, "Option");
+    let (value_ty, wraps_option) = match option_inner {
+        Some(inner) => (inner, true),
+        None => (ty, false),
+    };
+
+    let missing = match options.default {
+        Some(Some(expr)) => Missing::Default(quote!(#expr)),
+        Some(None) => Missing::Default(quote!(::core::default::Default::default())),
+        None if wraps_option && !options.required => Missing::Default(quote!(::core::option::Option::None)),
+        None => Missing::Error(options.error.unwrap_or_else(|| format_ident!("Requires{}", pascal_case(ident)))),
+    };
+
+    let each = match options.each {
+        Some(each) => match generic_argument_of(value_ty, "Vec") {
+            Some(item_ty) => Some((each, item_ty)),
+            None => return Err(Error::new_spanned(ty, "`each` can only be used on `Vec` fields")),
+        },
+        None => None,
+    };
+
+    Ok(BuilderField {
+        ident,
+        value_ty,
+        wraps_option,
+        missing,
+        each,
+    })
+}
+
+fn parse_struct_options(attrs: &[Attribute]) -> Result<StructOptions> {
+    let mut options = StructOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("error") {
+                options.error = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("name") {
+                options.name = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `error` or `name`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn parse_field_options(attrs: &[Attribute]) -> Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("required") {
+                options.required = true;
+            } else if meta.path.is_ident("default") {
+                options.default = Some(if meta.input.peek(syn::Token![=]) {
+                    Some(meta.value()?.parse()?)
+                } else {
+                    None
+                });
+            } else if meta.path.is_ident("each") {
+                options.each = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("error") {
+                options.error = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `required`, `default`, `each` or `error`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// Returns `T` when `ty` is spelled `Wrapper<T>` (with or without a leading path).
+fn generic_argument_of<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => match &arguments.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn pascal_case(ident: &Ident) -> String {
+    ident
+        .to_string()
+        .trim_start_matches("r#")
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,73 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod adapter;
+mod builder;
+
+/// Derives a builder implementing `design_patterns_rs::builder::Builder` for a struct.
+///
+/// For a struct `Person` this generates a `PersonBuilder` holding every field as an
+/// `Option`, a `with_*` setter per field, and a `build` that reports every missing
+/// field at once as a `Vec` of errors. Unless the struct names its own error type,
+/// a `PersonBuildingError` enum with one `Requires*` variant per required field is
+/// generated as well.
+///
+/// Struct attributes:
+/// - `#[builder(error = MyError)]` uses an existing enum for missing-field errors.
+/// - `#[builder(name = MyBuilder)]` renames the generated builder.
+///
+/// Field attributes:
+/// - `#[builder(required)]` makes an `Option<T>` field required; its setter takes `T`.
+/// - `#[builder(default)]` or `#[builder(default = expr)]` makes a field optional.
+/// - `#[builder(each = item)]` adds a `with_item` accumulator for a `Vec` field.
+/// - `#[builder(error = Variant)]` picks the error variant reported when missing.
+///
+/// Fields of type `Option<T>` are optional unless marked `required`; every other
+/// field without a default is required.
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    builder::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives an implementation of a trait for an enum of adaptees, each forwarding
+/// to the adaptee held by the current variant.
+///
+/// A derive can't see the trait's definition, so the signatures of the target
+/// trait's methods have to be restated in the enum attribute. They are copied into
+/// the generated impls as written, so they must match the trait's, and a mismatch
+/// is reported by the compiler against the generated impl. Every variant holds one
+/// adaptee, whose methods are called with the same name unless mapped to another
+/// one. The enum's `where` clause applies to the generated impls as well.
+///
+/// ```ignore
+/// #[derive(Adapter)]
+/// #[adapter(RangedWeapon {
+///     fn reload(&mut self) -> Result<(), WeaponError>;
+///     fn shoot(&mut self) -> Result<(), WeaponError>;
+/// })]
+/// enum Weapon {
+///     #[adapter(reload = draw_arrow, wrapper = BowAsRangedWeapon)]
+///     Bow(Bow),
+///     #[adapter(reload = load_bolt)]
+///     Crossbow(Crossbow),
+/// }
+/// ```
+///
+/// Variant attributes:
+/// - `#[adapter(method = adaptee_method)]` forwards `method` to `adaptee_method`.
+/// - `#[adapter(wrapper = Name)]` also generates a `Name` tuple struct adapting that
+///   variant's adaptee on its own.
+#[proc_macro_derive(Adapter, attributes(adapter))]
+pub fn derive_adapter(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    adapter::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
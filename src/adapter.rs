@@ -1,131 +1,414 @@
-#[cfg(test)]
-mod test {
-    trait ArrowShooter {
-        fn has_arrows_in_quiver(&self) -> bool;
-        fn draw_arrow(&mut self) -> bool;
-        fn can_shoot(&self) -> bool;
-        fn shoot(&mut self) -> bool;
+// Lets bows and crossbows, which are loaded and shot through different interfaces,
+// be used wherever a `RangedWeapon` is expected.
+//
+// `RangedWeaponAdapter` borrows the bow or crossbow it adapts. The open-ended
+// `ArrowShooterAsRangedWeapon` and `BoltShooterAsRangedWeapon` adapt any shooter,
+// held through a `WeaponHandle`: the shooter itself, a `Box`, an `Rc<RefCell<_>>`
+// or an `Arc<Mutex<_>>`, so they can be kept for as long as needed, or shared with
+// the rest of the program.
+
+pub use design_patterns_derive::Adapter;
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Why a weapon couldn't be reloaded or shot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponError {
+    OutOfAmmunition,
+    AlreadyLoaded,
+    NotLoaded,
+}
+
+impl fmt::Display for WeaponError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeaponError::OutOfAmmunition => write!(f, "no ammunition left"),
+            WeaponError::AlreadyLoaded => write!(f, "the weapon is already loaded"),
+            WeaponError::NotLoaded => write!(f, "the weapon is not loaded"),
+        }
     }
-    
-    struct Bow {
-        arrows_left_in_quiver: u32,
-        ready: bool
+}
+
+impl std::error::Error for WeaponError {}
+
+pub trait ArrowShooter {
+    fn has_arrows_in_quiver(&self) -> bool;
+    fn draw_arrow(&mut self) -> Result<(), WeaponError>;
+    fn can_shoot(&self) -> bool;
+    fn shoot(&mut self) -> Result<(), WeaponError>;
+}
+
+pub struct Bow {
+    arrows_left_in_quiver: u32,
+    ready: bool
+}
+
+impl Bow {
+    pub fn new(arrows_left_in_quiver: u32) -> Self {
+        Self { arrows_left_in_quiver, ready: false }
     }
-    
-    impl ArrowShooter for Bow {
-        fn has_arrows_in_quiver(&self) -> bool {
-            self.arrows_left_in_quiver > 0
-        }
-    
-        fn draw_arrow(&mut self) -> bool {
-            if self.ready && !self.has_arrows_in_quiver() {
-                return false
-            }
-            
-            self.arrows_left_in_quiver -= 1;
-            self.ready = true;
-            true
-        }
 
-        fn can_shoot(&self) -> bool {
-            self.ready
+    pub fn arrows_left_in_quiver(&self) -> u32 {
+        self.arrows_left_in_quiver
+    }
+
+    /// Puts arrows back in the quiver, the only way its count goes up.
+    pub fn restock(&mut self, arrows: u32) {
+        self.arrows_left_in_quiver = self.arrows_left_in_quiver.saturating_add(arrows);
+    }
+}
+
+impl ArrowShooter for Bow {
+    fn has_arrows_in_quiver(&self) -> bool {
+        self.arrows_left_in_quiver > 0
+    }
+
+    fn draw_arrow(&mut self) -> Result<(), WeaponError> {
+        if self.ready {
+            return Err(WeaponError::AlreadyLoaded)
         }
-    
-        fn shoot(&mut self) -> bool {
-            if !self.can_shoot() {
-                return false
-            }
-    
-            self.ready = false;
-            true
+        if !self.has_arrows_in_quiver() {
+            return Err(WeaponError::OutOfAmmunition)
         }
+
+        self.arrows_left_in_quiver -= 1;
+        self.ready = true;
+        Ok(())
     }
-    
-    trait BoltShooter {
-        fn has_bolts_stowed(&self) -> bool;
-        fn load_bolt(&mut self) -> bool;
-        fn can_shoot(&self) -> bool;
-        fn shoot(&mut self) -> bool;
-    }
-    
-    struct Crossbow {
-        ammunition_stowed: u32,
-        loaded: bool
-    }
-    
-    impl BoltShooter for Crossbow {
-        fn has_bolts_stowed(&self) -> bool {
-            self.ammunition_stowed > 0
-        }
-    
-        fn load_bolt(&mut self) -> bool {
-            if self.loaded && !self.has_bolts_stowed() {
-                return false
-            }
-            
-            self.ammunition_stowed -= 1;
-            self.loaded = true;
-            true
-        }
 
-        
-        fn can_shoot(&self) -> bool {
-            self.loaded
-        }
+    fn can_shoot(&self) -> bool {
+        self.ready
+    }
 
-        fn shoot(&mut self) -> bool {
-            if !self.can_shoot() {
-                return false
-            }
-    
-            self.loaded = false;
-            true
+    fn shoot(&mut self) -> Result<(), WeaponError> {
+        if !self.can_shoot() {
+            return Err(WeaponError::NotLoaded)
         }
 
+        self.ready = false;
+        Ok(())
     }
-    
-    trait RangedWeapon {
-        fn has_ammunition_left(&self) -> bool;
-        fn reload(&mut self) -> bool;
-        fn can_shoot(&self) -> bool;
-        fn shoot(&mut self) -> bool;
-    }
-    
-    enum RangedWeaponAdapter<'a> {
-        Bow(&'a mut Bow),
-        Crossbow(&'a mut Crossbow)
-    }
-    
-    impl<'a> RangedWeapon for RangedWeaponAdapter<'a> {
-        fn has_ammunition_left(&self) -> bool {
-            match self {
-                RangedWeaponAdapter::Bow(bow) => bow.has_arrows_in_quiver(),
-                RangedWeaponAdapter::Crossbow(crossbow) => crossbow.has_bolts_stowed(),
-            }
+}
+
+pub trait BoltShooter {
+    fn has_bolts_stowed(&self) -> bool;
+    fn load_bolt(&mut self) -> Result<(), WeaponError>;
+    fn can_shoot(&self) -> bool;
+    fn shoot(&mut self) -> Result<(), WeaponError>;
+}
+
+pub struct Crossbow {
+    ammunition_stowed: u32,
+    loaded: bool
+}
+
+impl Crossbow {
+    pub fn new(ammunition_stowed: u32) -> Self {
+        Self { ammunition_stowed, loaded: false }
+    }
+
+    pub fn ammunition_stowed(&self) -> u32 {
+        self.ammunition_stowed
+    }
+
+    /// Stows more bolts, the only way the stowed count goes up.
+    pub fn restock(&mut self, bolts: u32) {
+        self.ammunition_stowed = self.ammunition_stowed.saturating_add(bolts);
+    }
+}
+
+impl BoltShooter for Crossbow {
+    fn has_bolts_stowed(&self) -> bool {
+        self.ammunition_stowed > 0
+    }
+
+    fn load_bolt(&mut self) -> Result<(), WeaponError> {
+        if self.loaded {
+            return Err(WeaponError::AlreadyLoaded)
         }
-    
-        fn reload(&mut self) -> bool {
-            match self {
-                RangedWeaponAdapter::Bow(bow) => bow.draw_arrow(),
-                RangedWeaponAdapter::Crossbow(crossbow) => crossbow.load_bolt(),
-            }
+        if !self.has_bolts_stowed() {
+            return Err(WeaponError::OutOfAmmunition)
+        }
+
+        self.ammunition_stowed -= 1;
+        self.loaded = true;
+        Ok(())
+    }
+
+    fn can_shoot(&self) -> bool {
+        self.loaded
+    }
+
+    fn shoot(&mut self) -> Result<(), WeaponError> {
+        if !self.can_shoot() {
+            return Err(WeaponError::NotLoaded)
         }
-    
-        fn shoot(&mut self) -> bool {
-            match self {
-                RangedWeaponAdapter::Bow(bow) => bow.shoot(),
-                RangedWeaponAdapter::Crossbow(crossbow) => crossbow.shoot(),
+
+        self.loaded = false;
+        Ok(())
+    }
+}
+
+pub trait RangedWeapon {
+    fn has_ammunition_left(&self) -> bool;
+    fn reload(&mut self) -> Result<(), WeaponError>;
+    fn can_shoot(&self) -> bool;
+    fn shoot(&mut self) -> Result<(), WeaponError>;
+}
+
+#[derive(Adapter)]
+#[adapter(RangedWeapon {
+    fn has_ammunition_left(&self) -> bool;
+    fn reload(&mut self) -> Result<(), WeaponError>;
+    fn can_shoot(&self) -> bool;
+    fn shoot(&mut self) -> Result<(), WeaponError>;
+})]
+pub enum RangedWeaponAdapter<'a> {
+    #[adapter(has_ammunition_left = has_arrows_in_quiver, reload = draw_arrow)]
+    Bow(&'a mut Bow),
+    #[adapter(has_ammunition_left = has_bolts_stowed, reload = load_bolt)]
+    Crossbow(&'a mut Crossbow)
+}
+
+/// Gives access to a weapon, however it's held.
+pub trait WeaponHandle<W: ?Sized> {
+    fn with<R, F: FnOnce(&W) -> R>(&self, f: F) -> R;
+
+    fn with_mut<R, F: FnOnce(&mut W) -> R>(&mut self, f: F) -> R;
+}
+
+impl<W: ?Sized> WeaponHandle<W> for W {
+    fn with<R, F: FnOnce(&W) -> R>(&self, f: F) -> R {
+        f(self)
+    }
+
+    fn with_mut<R, F: FnOnce(&mut W) -> R>(&mut self, f: F) -> R {
+        f(self)
+    }
+}
+
+impl<W: ?Sized> WeaponHandle<W> for Box<W> {
+    fn with<R, F: FnOnce(&W) -> R>(&self, f: F) -> R {
+        f(self)
+    }
+
+    fn with_mut<R, F: FnOnce(&mut W) -> R>(&mut self, f: F) -> R {
+        f(self)
+    }
+}
+
+impl<W: ?Sized> WeaponHandle<W> for &mut W {
+    fn with<R, F: FnOnce(&W) -> R>(&self, f: F) -> R {
+        f(self)
+    }
+
+    fn with_mut<R, F: FnOnce(&mut W) -> R>(&mut self, f: F) -> R {
+        f(self)
+    }
+}
+
+/// Panics if the weapon is already borrowed elsewhere.
+impl<W: ?Sized> WeaponHandle<W> for Rc<RefCell<W>> {
+    fn with<R, F: FnOnce(&W) -> R>(&self, f: F) -> R {
+        f(&self.borrow())
+    }
+
+    fn with_mut<R, F: FnOnce(&mut W) -> R>(&mut self, f: F) -> R {
+        f(&mut self.borrow_mut())
+    }
+}
+
+/// Blocks until the weapon is free. A weapon whose previous user panicked is
+/// still used, since its state is valid after every operation.
+impl<W: ?Sized> WeaponHandle<W> for Arc<Mutex<W>> {
+    fn with<R, F: FnOnce(&W) -> R>(&self, f: F) -> R {
+        f(&self.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn with_mut<R, F: FnOnce(&mut W) -> R>(&mut self, f: F) -> R {
+        f(&mut self.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Adapts any `ArrowShooter`, held through `H`, which may be the shooter itself or,
+/// with `T = dyn ArrowShooter`, a boxed trait object.
+pub struct ArrowShooterAsRangedWeapon<T: ?Sized, H = T> {
+    handle: H,
+    shooter: PhantomData<fn() -> T>,
+}
+
+impl<T: ArrowShooter + ?Sized, H: WeaponHandle<T>> ArrowShooterAsRangedWeapon<T, H> {
+    pub fn new(shooter: H) -> Self {
+        Self { handle: shooter, shooter: PhantomData }
+    }
+
+    /// Gives back the adapted shooter.
+    pub fn into_inner(self) -> H {
+        self.handle
+    }
+}
+
+impl<T: ArrowShooter + ?Sized, H: WeaponHandle<T>> RangedWeapon for ArrowShooterAsRangedWeapon<T, H> {
+    fn has_ammunition_left(&self) -> bool {
+        self.handle.with(|shooter| shooter.has_arrows_in_quiver())
+    }
+
+    fn reload(&mut self) -> Result<(), WeaponError> {
+        self.handle.with_mut(|shooter| shooter.draw_arrow())
+    }
+
+    fn can_shoot(&self) -> bool {
+        self.handle.with(|shooter| shooter.can_shoot())
+    }
+
+    fn shoot(&mut self) -> Result<(), WeaponError> {
+        self.handle.with_mut(|shooter| shooter.shoot())
+    }
+}
+
+/// Adapts any `BoltShooter`, held through `H`, which may be the shooter itself or,
+/// with `T = dyn BoltShooter`, a boxed trait object.
+pub struct BoltShooterAsRangedWeapon<T: ?Sized, H = T> {
+    handle: H,
+    shooter: PhantomData<fn() -> T>,
+}
+
+impl<T: BoltShooter + ?Sized, H: WeaponHandle<T>> BoltShooterAsRangedWeapon<T, H> {
+    pub fn new(shooter: H) -> Self {
+        Self { handle: shooter, shooter: PhantomData }
+    }
+
+    /// Gives back the adapted shooter.
+    pub fn into_inner(self) -> H {
+        self.handle
+    }
+}
+
+impl<T: BoltShooter + ?Sized, H: WeaponHandle<T>> RangedWeapon for BoltShooterAsRangedWeapon<T, H> {
+    fn has_ammunition_left(&self) -> bool {
+        self.handle.with(|shooter| shooter.has_bolts_stowed())
+    }
+
+    fn reload(&mut self) -> Result<(), WeaponError> {
+        self.handle.with_mut(|shooter| shooter.load_bolt())
+    }
+
+    fn can_shoot(&self) -> bool {
+        self.handle.with(|shooter| shooter.can_shoot())
+    }
+
+    fn shoot(&mut self) -> Result<(), WeaponError> {
+        self.handle.with_mut(|shooter| shooter.shoot())
+    }
+}
+
+/// Adapts a bow held through `H`, which may be the bow itself.
+pub type BowAdapter<H = Bow> = ArrowShooterAsRangedWeapon<Bow, H>;
+
+/// Adapts a crossbow held through `H`, which may be the crossbow itself.
+pub type CrossbowAdapter<H = Crossbow> = BoltShooterAsRangedWeapon<Crossbow, H>;
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[derive(Debug, Clone)]
+    enum Action {
+        Reload,
+        Shoot,
+        Restock(u32),
+    }
+
+    fn actions() -> impl Strategy<Value = Vec<Action>> {
+        let action = prop_oneof![
+            3 => Just(Action::Reload),
+            3 => Just(Action::Shoot),
+            1 => (0..5u32).prop_map(Action::Restock),
+        ];
+        prop::collection::vec(action, 0..64)
+    }
+
+    proptest! {
+        #[test]
+        fn bow_ammunition_only_grows_when_restocked(arrows in 0..4u32, actions in actions()) {
+            let mut bow = Bow::new(arrows);
+
+            for action in actions {
+                let (before, was_ready) = (bow.arrows_left_in_quiver(), bow.can_shoot());
+                match action {
+                    Action::Reload => match bow.draw_arrow() {
+                        Ok(()) => prop_assert_eq!(bow.arrows_left_in_quiver(), before - 1),
+                        Err(error) => {
+                            let expected = if was_ready { WeaponError::AlreadyLoaded } else { WeaponError::OutOfAmmunition };
+                            prop_assert_eq!(error, expected);
+                            prop_assert_eq!(bow.arrows_left_in_quiver(), before);
+                        }
+                    },
+                    Action::Shoot => {
+                        prop_assert_eq!(bow.shoot().is_ok(), was_ready);
+                        prop_assert_eq!(bow.arrows_left_in_quiver(), before);
+                    }
+                    Action::Restock(arrows) => {
+                        bow.restock(arrows);
+                        prop_assert_eq!(bow.arrows_left_in_quiver(), before + arrows);
+                    }
+                }
             }
         }
 
-        fn can_shoot(&self) -> bool {
-            match self {
-                RangedWeaponAdapter::Bow(bow) => bow.can_shoot(),
-                RangedWeaponAdapter::Crossbow(crossbow) => crossbow.can_shoot(),
+        #[test]
+        fn crossbow_ammunition_only_grows_when_restocked(bolts in 0..4u32, actions in actions()) {
+            let mut crossbow = Crossbow::new(bolts);
+
+            for action in actions {
+                let (before, was_loaded) = (crossbow.ammunition_stowed(), crossbow.can_shoot());
+                match action {
+                    Action::Reload => match crossbow.load_bolt() {
+                        Ok(()) => prop_assert_eq!(crossbow.ammunition_stowed(), before - 1),
+                        Err(error) => {
+                            let expected = if was_loaded { WeaponError::AlreadyLoaded } else { WeaponError::OutOfAmmunition };
+                            prop_assert_eq!(error, expected);
+                            prop_assert_eq!(crossbow.ammunition_stowed(), before);
+                        }
+                    },
+                    Action::Shoot => {
+                        prop_assert_eq!(crossbow.shoot().is_ok(), was_loaded);
+                        prop_assert_eq!(crossbow.ammunition_stowed(), before);
+                    }
+                    Action::Restock(bolts) => {
+                        crossbow.restock(bolts);
+                        prop_assert_eq!(crossbow.ammunition_stowed(), before + bolts);
+                    }
+                }
             }
         }
     }
 
+    #[test]
+    fn empty_weapons_refuse_to_reload() {
+        let mut bow = Bow::new(0);
+        let mut crossbow = Crossbow::new(0);
+
+        assert_eq!(bow.draw_arrow(), Err(WeaponError::OutOfAmmunition));
+        assert_eq!(crossbow.load_bolt(), Err(WeaponError::OutOfAmmunition));
+        assert_eq!(bow.arrows_left_in_quiver(), 0);
+    }
+
+    #[test]
+    fn loaded_weapons_keep_their_ammunition() {
+        let mut bow = Bow::new(2);
+
+        bow.draw_arrow().unwrap();
+
+        assert_eq!(bow.draw_arrow(), Err(WeaponError::AlreadyLoaded));
+        assert_eq!(bow.arrows_left_in_quiver(), 1);
+    }
+
     #[test]
     fn adapter_works_for_bow() {
         let mut bow = Bow {
@@ -140,10 +423,10 @@ mod test {
 
         assert_eq!(adapter.has_ammunition_left(), true);
         assert_eq!(adapter.can_shoot(), false);
-        assert_eq!(adapter.reload(), true);
+        assert_eq!(adapter.reload(), Ok(()));
         assert_eq!(adapter.has_ammunition_left(), false);
         assert_eq!(adapter.can_shoot(), true);
-        assert_eq!(adapter.shoot(), true);
+        assert_eq!(adapter.shoot(), Ok(()));
         assert_eq!(adapter.has_ammunition_left(), false);
         assert_eq!(adapter.can_shoot(), false);
         assert_eq!(bow.has_arrows_in_quiver(), false);
@@ -164,13 +447,304 @@ mod test {
 
         assert_eq!(adapter.has_ammunition_left(), true);
         assert_eq!(adapter.can_shoot(), false);
-        assert_eq!(adapter.reload(), true);
+        assert_eq!(adapter.reload(), Ok(()));
         assert_eq!(adapter.has_ammunition_left(), false);
         assert_eq!(adapter.can_shoot(), true);
-        assert_eq!(adapter.shoot(), true);
+        assert_eq!(adapter.shoot(), Ok(()));
         assert_eq!(adapter.has_ammunition_left(), false);
         assert_eq!(adapter.can_shoot(), false);
         assert_eq!(crossbow.has_bolts_stowed(), false);
         assert_eq!(crossbow.can_shoot(), false);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn owned_adapters_can_be_stored() {
+        let mut arsenal: Vec<Box<dyn RangedWeapon>> = vec![
+            Box::new(BowAdapter::new(Bow::new(2))),
+            Box::new(CrossbowAdapter::new(Box::new(Crossbow::new(1)))),
+        ];
+
+        for weapon in arsenal.iter_mut() {
+            weapon.reload().unwrap();
+            weapon.shoot().unwrap();
+        }
+
+        assert!(arsenal[0].has_ammunition_left());
+        assert!(!arsenal[1].has_ammunition_left());
+    }
+
+    #[test]
+    fn adapters_give_their_weapon_back() {
+        let mut adapter = BowAdapter::new(Bow::new(3));
+        adapter.reload().unwrap();
+
+        let bow = adapter.into_inner();
+
+        assert_eq!(bow.arrows_left_in_quiver(), 2);
+        assert!(bow.can_shoot());
+    }
+
+    #[test]
+    fn adapters_share_their_weapon() {
+        let bow = Rc::new(RefCell::new(Bow::new(1)));
+        let mut adapter = BowAdapter::new(Rc::clone(&bow));
+
+        adapter.reload().unwrap();
+
+        assert!(bow.borrow().can_shoot());
+        bow.borrow_mut().shoot().unwrap();
+        assert!(!adapter.can_shoot());
+    }
+
+    #[test]
+    fn adapters_can_be_sent_across_threads() {
+        let crossbow = Arc::new(Mutex::new(Crossbow::new(2)));
+        let mut adapter: Box<dyn RangedWeapon + Send> = Box::new(CrossbowAdapter::new(Arc::clone(&crossbow)));
+
+        std::thread::spawn(move || {
+            adapter.reload().unwrap();
+            adapter.shoot().unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(crossbow.lock().unwrap().ammunition_stowed(), 1);
+    }
+
+    trait StoneThrower {
+        fn has_stones(&self) -> bool;
+        fn pick_up_stone(&mut self) -> Result<(), WeaponError>;
+        fn can_shoot(&self) -> bool;
+        fn shoot(&mut self) -> Result<(), WeaponError>;
+    }
+
+    struct Sling {
+        stones: u32,
+        loaded: bool,
+    }
+
+    impl StoneThrower for Sling {
+        fn has_stones(&self) -> bool {
+            self.stones > 0
+        }
+
+        fn pick_up_stone(&mut self) -> Result<(), WeaponError> {
+            if self.loaded {
+                return Err(WeaponError::AlreadyLoaded);
+            }
+            if !self.has_stones() {
+                return Err(WeaponError::OutOfAmmunition);
+            }
+            self.stones -= 1;
+            self.loaded = true;
+            Ok(())
+        }
+
+        fn can_shoot(&self) -> bool {
+            self.loaded
+        }
+
+        fn shoot(&mut self) -> Result<(), WeaponError> {
+            if !self.loaded {
+                return Err(WeaponError::NotLoaded);
+            }
+            self.loaded = false;
+            Ok(())
+        }
+    }
+
+    #[derive(Adapter)]
+    #[adapter(RangedWeapon {
+        fn has_ammunition_left(&self) -> bool;
+        fn reload(&mut self) -> Result<(), WeaponError>;
+        fn can_shoot(&self) -> bool;
+        fn shoot(&mut self) -> Result<(), WeaponError>;
+    })]
+    enum Armory<'a> {
+        #[adapter(has_ammunition_left = has_stones, reload = pick_up_stone, wrapper = SlingAdapter)]
+        Sling(Sling),
+        #[adapter(has_ammunition_left = has_arrows_in_quiver, reload = draw_arrow)]
+        Bow(Box<Bow>),
+        #[adapter(has_ammunition_left = has_bolts_stowed, reload = load_bolt, wrapper = BorrowedCrossbow)]
+        Crossbow(&'a mut Crossbow),
+    }
+
+    #[test]
+    fn derived_adapter_works_for_a_new_weapon() {
+        let mut adapter = Armory::Sling(Sling { stones: 1, loaded: false });
+
+        assert!(adapter.has_ammunition_left());
+        assert_eq!(adapter.reload(), Ok(()));
+        assert_eq!(adapter.reload(), Err(WeaponError::AlreadyLoaded));
+        assert_eq!(adapter.shoot(), Ok(()));
+        assert_eq!(adapter.reload(), Err(WeaponError::OutOfAmmunition));
+    }
+
+    #[test]
+    fn derived_adapter_holds_any_kind_of_adaptee() {
+        let mut crossbow = Crossbow::new(1);
+        let mut weapons = [Armory::Bow(Box::new(Bow::new(1))), Armory::Crossbow(&mut crossbow)];
+
+        for weapon in weapons.iter_mut() {
+            weapon.reload().unwrap();
+            assert!(weapon.can_shoot());
+            assert!(!weapon.has_ammunition_left());
+        }
+    }
+
+    #[test]
+    fn derived_wrappers_adapt_a_single_weapon() {
+        let mut sling = SlingAdapter(Sling { stones: 2, loaded: false });
+        sling.reload().unwrap();
+        sling.shoot().unwrap();
+
+        let mut crossbow = Crossbow::new(0);
+        let mut borrowed = BorrowedCrossbow(&mut crossbow);
+
+        assert_eq!(sling.0.stones, 1);
+        assert_eq!(borrowed.reload(), Err(WeaponError::OutOfAmmunition));
+    }
+
+    struct Longbow {
+        arrows: u32,
+        drawn: bool,
+    }
+
+    impl ArrowShooter for Longbow {
+        fn has_arrows_in_quiver(&self) -> bool {
+            self.arrows > 0
+        }
+
+        fn draw_arrow(&mut self) -> Result<(), WeaponError> {
+            if self.drawn {
+                return Err(WeaponError::AlreadyLoaded);
+            }
+            if self.arrows == 0 {
+                return Err(WeaponError::OutOfAmmunition);
+            }
+            self.arrows -= 1;
+            self.drawn = true;
+            Ok(())
+        }
+
+        fn can_shoot(&self) -> bool {
+            self.drawn
+        }
+
+        fn shoot(&mut self) -> Result<(), WeaponError> {
+            if !self.drawn {
+                return Err(WeaponError::NotLoaded);
+            }
+            self.drawn = false;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn adapts_any_arrow_shooter() {
+        let mut adapter = ArrowShooterAsRangedWeapon::new(Longbow { arrows: 1, drawn: false });
+
+        adapter.reload().unwrap();
+        adapter.shoot().unwrap();
+
+        assert!(!adapter.has_ammunition_left());
+        assert_eq!(adapter.into_inner().arrows, 0);
+    }
+
+    #[test]
+    fn adapts_shooter_trait_objects() {
+        let shooters: Vec<Box<dyn ArrowShooter>> = vec![Box::new(Bow::new(1)), Box::new(Longbow { arrows: 0, drawn: false })];
+        let mut weapons: Vec<ArrowShooterAsRangedWeapon<dyn ArrowShooter, Box<dyn ArrowShooter>>> =
+            shooters.into_iter().map(ArrowShooterAsRangedWeapon::new).collect();
+
+        assert_eq!(weapons[0].reload(), Ok(()));
+        assert_eq!(weapons[1].reload(), Err(WeaponError::OutOfAmmunition));
+
+        let bolts: Box<dyn BoltShooter> = Box::new(Crossbow::new(1));
+        let mut crossbow = BoltShooterAsRangedWeapon::<dyn BoltShooter, _>::new(bolts);
+        crossbow.reload().unwrap();
+        assert!(crossbow.can_shoot());
+    }
+
+    trait Reloadable {
+        fn reload(&mut self) -> Result<(), WeaponError>;
+    }
+
+    #[derive(Adapter)]
+    #[adapter(Reloadable {
+        fn reload(&mut self) -> Result<(), WeaponError>;
+    })]
+    enum AnyShooter<A, B>
+    where
+        A: ArrowShooter,
+        B: BoltShooter,
+    {
+        #[adapter(reload = draw_arrow, wrapper = AnyArrowShooter)]
+        Arrows(A),
+        #[adapter(reload = load_bolt)]
+        Bolts(B),
+    }
+
+    #[test]
+    fn derived_adapter_keeps_the_where_clause() {
+        let mut shooters: [AnyShooter<Longbow, Crossbow>; 2] =
+            [AnyShooter::Arrows(Longbow { arrows: 1, drawn: false }), AnyShooter::Bolts(Crossbow::new(0))];
+
+        assert_eq!(shooters[0].reload(), Ok(()));
+        assert_eq!(shooters[1].reload(), Err(WeaponError::OutOfAmmunition));
+
+        let mut longbow = AnyArrowShooter(Longbow { arrows: 1, drawn: false });
+        longbow.reload().unwrap();
+        assert!(longbow.0.can_shoot());
+    }
+
+    trait Quiver {
+        fn take(&mut self, adaptee: u32) -> u32;
+    }
+
+    struct ArrowBundle(u32);
+
+    impl ArrowBundle {
+        fn take(&mut self, arrows: u32) -> u32 {
+            let taken = arrows.min(self.0);
+            self.0 -= taken;
+            taken
+        }
+    }
+
+    #[derive(Adapter)]
+    #[adapter(Quiver {
+        fn take(&mut self, adaptee: u32) -> u32;
+    })]
+    enum Quivers {
+        Bundle(ArrowBundle),
+    }
+
+    #[test]
+    fn derived_adapter_forwards_parameters_named_adaptee() {
+        let mut quiver = Quivers::Bundle(ArrowBundle(3));
+
+        assert_eq!(quiver.take(2), 2);
+        assert_eq!(quiver.take(2), 1);
+    }
+
+    #[test]
+    fn adapter_explains_failures() {
+        let mut crossbow = Crossbow::new(0);
+        let mut adapter = RangedWeaponAdapter::Crossbow(&mut crossbow);
+
+        let error = adapter.shoot().unwrap_err();
+
+        assert_eq!(error, WeaponError::NotLoaded);
+        assert_eq!(error.to_string(), "the weapon is not loaded");
+    }
+
+    #[test]
+    fn weapons_are_built_unloaded() {
+        let bow = Bow::new(12);
+
+        assert_eq!(bow.arrows_left_in_quiver(), 12);
+        assert!(!bow.can_shoot());
+        assert_eq!(Crossbow::new(3).ammunition_stowed(), 3);
+    }
+}
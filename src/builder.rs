@@ -1,9 +1,29 @@
+pub use design_patterns_derive::Builder;
+
+use crate::builder_non_consuming;
+
 pub trait Builder<T, E> {
     /// fn with_t(&mut self, t: T) -> &mut Self {
     fn build(self) -> Result<T, E>;
+
+    /// Builds from the current state and leaves a fresh builder in its place.
+    fn build_and_reset(&mut self) -> Result<T, E>
+    where
+        Self: Sized + Default,
+    {
+        std::mem::take(self).build()
+    }
+}
+
+// Anything that builds from `&self` can also be handed over and consumed.
+impl<T, E, B: builder_non_consuming::Builder<T, E>> Builder<T, E> for B {
+    fn build(self) -> Result<T, E> {
+        builder_non_consuming::Builder::build(&self)
+    }
 }
 
 #[cfg(test)]
+#[allow(unused_must_use, clippy::bool_assert_comparison, clippy::unnecessary_operation)]
 mod tests {
     use super::*;
     use std::borrow::BorrowMut;
@@ -202,4 +222,135 @@ mod tests {
         assert_eq!(person_result.is_err(), true);
         assert_eq!(person_result.unwrap_err(), vec![PersonBuildingError::RequiresFavoriteThingsList]);
     }
+
+    mod derived {
+        use crate::builder::Builder;
+
+        #[derive(Debug, PartialEq)]
+        enum PersonBuildingError {
+            RequiresName,
+            RequiresFavoriteThingsList,
+        }
+
+        #[derive(Debug, PartialEq, Builder)]
+        #[builder(error = PersonBuildingError)]
+        struct Person {
+            name: String,
+            birthdate: Option<String>,
+            #[builder(each = favorite_thing, error = RequiresFavoriteThingsList)]
+            favorite_things: Vec<String>,
+        }
+
+        #[derive(Debug, PartialEq, Builder)]
+        struct Character {
+            name: String,
+            #[builder(default = 1)]
+            level: u32,
+            #[builder(default)]
+            titles: Vec<String>,
+            #[builder(required)]
+            guild: Option<String>,
+        }
+
+        #[test]
+        fn builds_a_person_with_all_fields() {
+            let mut person_builder = PersonBuilder::new();
+
+            person_builder.with_name(String::from("Evangivaldo"))
+                .with_birthdate(String::from("25/12/1988"))
+                .with_favorite_thing(String::from("Games"))
+                .with_favorite_thing(String::from("Traveling"));
+
+            let person = person_builder.build().unwrap();
+
+            assert_eq!(person, Person {
+                name: String::from("Evangivaldo"),
+                birthdate: Some(String::from("25/12/1988")),
+                favorite_things: vec![String::from("Games"), String::from("Traveling")],
+            });
+        }
+
+        #[test]
+        fn sets_the_whole_list_at_once() {
+            let mut person_builder = PersonBuilder::new();
+
+            person_builder.with_name(String::from("Evangivaldo"))
+                .with_favorite_things(vec![String::from("Games")])
+                .with_favorite_thing(String::from("Traveling"));
+
+            let person = person_builder.build().unwrap();
+
+            assert_eq!(person.birthdate, None);
+            assert_eq!(person.favorite_things, vec!["Games", "Traveling"]);
+        }
+
+        #[test]
+        fn fails_building_person_with_no_fields() {
+            let person_result = PersonBuilder::new()
+                .build();
+
+            assert_eq!(person_result.unwrap_err(), vec![
+                PersonBuildingError::RequiresName,
+                PersonBuildingError::RequiresFavoriteThingsList
+            ]);
+        }
+
+        #[test]
+        fn fails_building_person_without_favorite_things() {
+            let mut person_builder = PersonBuilder::new();
+
+            person_builder
+                .with_name(String::from("Evangivaldo"))
+                .with_birthdate(String::from("25/12/1988"));
+
+            let person_result = person_builder.build();
+
+            assert_eq!(person_result.unwrap_err(), vec![PersonBuildingError::RequiresFavoriteThingsList]);
+        }
+
+        #[test]
+        fn falls_back_to_defaults() {
+            let mut character_builder = CharacterBuilder::new();
+
+            character_builder
+                .with_name(String::from("Fulano"))
+                .with_guild(String::from("Rangers"));
+
+            let character = character_builder.build().unwrap();
+
+            assert_eq!(character, Character {
+                name: String::from("Fulano"),
+                level: 1,
+                titles: vec![],
+                guild: Some(String::from("Rangers")),
+            });
+        }
+
+        #[test]
+        fn builds_and_resets() {
+            let mut character_builder = CharacterBuilder::new();
+
+            character_builder
+                .with_name(String::from("Fulano"))
+                .with_guild(String::from("Rangers"));
+
+            assert!(character_builder.build_and_reset().is_ok());
+            assert_eq!(character_builder.build_and_reset().unwrap_err(), vec![
+                CharacterBuildingError::RequiresName,
+                CharacterBuildingError::RequiresGuild
+            ]);
+        }
+
+        #[test]
+        fn generates_an_error_per_required_field() {
+            let mut character_builder = CharacterBuilder::new();
+
+            character_builder.with_level(10);
+
+            assert_eq!(character_builder.build().unwrap_err(), vec![
+                CharacterBuildingError::RequiresName,
+                CharacterBuildingError::RequiresGuild
+            ]);
+        }
+    }
 }
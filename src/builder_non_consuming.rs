@@ -1,9 +1,19 @@
+/// Builders that can build from `&self` and are reusable as templates.
+///
+/// Every implementor is also a consuming `builder::Builder`, so code taking
+/// builders can bound on that trait and accept either kind.
 pub trait Builder<T, E> {
     /// fn with_t(&mut self, t: T) -> &mut Self {
     fn build(&self) -> Result<T, E>;
+
+    /// Builds `n` values from the same state, stopping at the first error.
+    fn build_many(&self, n: usize) -> Result<Vec<T>, E> {
+        (0..n).map(|_| self.build()).collect()
+    }
 }
 
 #[cfg(test)]
+#[allow(unused_must_use, clippy::bool_assert_comparison, clippy::unnecessary_operation)]
 mod tests {
     use super::*;
     use std::borrow::BorrowMut;
@@ -21,7 +31,7 @@ mod tests {
         RequiresFavoriteThingsList,
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     struct PersonBuilder {
         name: Option<String>,
         birthdate: Option<String>,
@@ -198,4 +208,56 @@ mod tests {
         assert_eq!(person_result.is_err(), true);
         assert_eq!(person_result.unwrap_err(), vec![PersonBuildingError::RequiresFavoriteThingsList]);
     }
+
+    #[test]
+    fn builds_many_people_from_one_template() {
+        let mut person_builder = PersonBuilder::new();
+
+        person_builder
+            .with_name(String::from("Fulano"))
+            .with_favorite_thing(String::from("Games"));
+
+        let people = person_builder.build_many(3).unwrap();
+
+        assert_eq!(people.len(), 3);
+        assert!(people.iter().all(|person| person.name == "Fulano"));
+        assert_eq!(PersonBuilder::new().build_many(2).unwrap_err(), vec![
+            PersonBuildingError::RequiresName,
+            PersonBuildingError::RequiresFavoriteThingsList
+        ]);
+    }
+
+    fn build_consuming<B: crate::builder::Builder<Person, Vec<PersonBuildingError>>>(builder: B) -> Result<Person, Vec<PersonBuildingError>> {
+        builder.build()
+    }
+
+    #[test]
+    fn can_be_used_as_a_consuming_builder() {
+        let mut person_builder = PersonBuilder::new();
+
+        person_builder
+            .with_name(String::from("Fulano"))
+            .with_favorite_thing(String::from("Games"));
+
+        let person = build_consuming(person_builder).unwrap();
+
+        assert_eq!(person.name, "Fulano");
+    }
+
+    #[test]
+    fn builds_and_resets_the_template() {
+        use crate::builder::Builder as _;
+
+        let mut person_builder = PersonBuilder::new();
+
+        person_builder
+            .with_name(String::from("Fulano"))
+            .with_favorite_thing(String::from("Games"));
+
+        assert!(person_builder.build_and_reset().is_ok());
+        assert_eq!(person_builder.build().unwrap_err(), vec![
+            PersonBuildingError::RequiresName,
+            PersonBuildingError::RequiresFavoriteThingsList
+        ]);
+    }
 }
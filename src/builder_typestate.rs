@@ -0,0 +1,215 @@
+// Typestate builders track every required field in the builder's type parameters,
+// so `build` only exists once all of them have been set. Fully set builders
+// implement `builder_non_consuming::Builder`, which makes them consuming
+// `builder::Builder`s as well, with `Infallible` as the error since nothing can
+// be missing anymore.
+
+/// State of a required field that has not been given a value yet.
+///
+/// A builder can only build once none of its fields are `Unset`. Take a
+/// `PersonBuilder<N, F>` whose `with_name` sets `N`, whose `with_favorite_thing`
+/// pushes onto `F`, and which implements `builder_non_consuming::Builder` once
+/// both are `Set`. It builds from a reference, and consumed as well:
+///
+/// ```
+/// # include!("builder_typestate/person_builder.rs");
+/// # fn main() {
+/// use design_patterns_rs::{builder, builder_non_consuming};
+///
+/// let person_builder = PersonBuilder::new().with_name("Fulano").with_favorite_thing("Games");
+/// let first = builder_non_consuming::Builder::build(&person_builder).unwrap();
+/// let second = builder::Builder::build(person_builder.with_favorite_thing("Traveling")).unwrap();
+///
+/// assert_eq!(first.name, "Fulano");
+/// assert_eq!(first.favorite_things, ["Games"]);
+/// assert_eq!(second.favorite_things, ["Games", "Traveling"]);
+/// # }
+/// ```
+///
+/// Without a favorite thing, neither the consuming `build`:
+///
+/// ```compile_fail,E0599
+/// # include!("builder_typestate/person_builder.rs");
+/// # fn main() {
+/// use design_patterns_rs::builder::Builder;
+///
+/// PersonBuilder::new().with_name("Fulano").build();
+/// # }
+/// ```
+///
+/// nor the non-consuming one exists:
+///
+/// ```compile_fail,E0599
+/// # include!("builder_typestate/person_builder.rs");
+/// # fn main() {
+/// use design_patterns_rs::builder_non_consuming::Builder;
+///
+/// PersonBuilder::new().with_name("Fulano").build();
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Unset;
+
+/// State of a required field holding its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Set<T>(pub T);
+
+impl<T> Set<T> {
+    pub fn get(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Accumulates items into a required list field, like `with_favorite_thing`.
+///
+/// Pushing onto an `Unset` field starts a new list, so the field becomes `Set`
+/// after the first item.
+pub trait Push<T> {
+    fn push(self, item: T) -> Set<Vec<T>>;
+}
+
+impl<T> Push<T> for Unset {
+    fn push(self, item: T) -> Set<Vec<T>> {
+        Set(vec![item])
+    }
+}
+
+impl<T> Push<T> for Set<Vec<T>> {
+    fn push(mut self, item: T) -> Set<Vec<T>> {
+        self.0.push(item);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder, builder_non_consuming};
+    use std::convert::Infallible;
+
+    #[derive(Debug, PartialEq)]
+    struct Person {
+        pub name: String,
+        pub birthdate: Option<String>,
+        pub favorite_things: Vec<String>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct PersonBuilder<N, F> {
+        name: N,
+        birthdate: Option<String>,
+        favorite_things: F,
+    }
+
+    impl PersonBuilder<Unset, Unset> {
+        fn new() -> Self {
+            Self {
+                name: Unset,
+                birthdate: None,
+                favorite_things: Unset
+            }
+        }
+    }
+
+    impl<N, F> PersonBuilder<N, F> {
+        fn with_name(self, name: String) -> PersonBuilder<Set<String>, F> {
+            PersonBuilder {
+                name: Set(name),
+                birthdate: self.birthdate,
+                favorite_things: self.favorite_things,
+            }
+        }
+
+        fn with_birthdate(mut self, birthdate: String) -> Self {
+            self.birthdate = Some(birthdate);
+            self
+        }
+
+        fn with_favorite_thing(self, favorite_thing: String) -> PersonBuilder<N, Set<Vec<String>>>
+        where
+            F: Push<String>,
+        {
+            PersonBuilder {
+                name: self.name,
+                birthdate: self.birthdate,
+                favorite_things: self.favorite_things.push(favorite_thing),
+            }
+        }
+    }
+
+    impl builder_non_consuming::Builder<Person, Infallible> for PersonBuilder<Set<String>, Set<Vec<String>>> {
+        fn build(&self) -> Result<Person, Infallible> {
+            Ok(Person {
+                name: self.name.get().clone(),
+                birthdate: self.birthdate.clone(),
+                favorite_things: self.favorite_things.get().clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn builds_a_person_with_all_fields() {
+        use builder::Builder;
+
+        let person = PersonBuilder::new()
+            .with_name(String::from("Evangivaldo"))
+            .with_birthdate(String::from("25/12/1988"))
+            .with_favorite_thing(String::from("Games"))
+            .with_favorite_thing(String::from("Traveling"))
+            .build()
+            .unwrap();
+
+        assert_eq!(person.name, "Evangivaldo");
+        assert_eq!(person.birthdate, Some(String::from("25/12/1988")));
+        assert_eq!(person.favorite_things, vec!["Games", "Traveling"]);
+    }
+
+    #[test]
+    fn builds_person_without_birthdate_in_any_order() {
+        use builder::Builder;
+
+        let person = PersonBuilder::new()
+            .with_favorite_thing(String::from("Games"))
+            .with_name(String::from("Evangivaldo"))
+            .build()
+            .unwrap();
+
+        assert_eq!(person.name, "Evangivaldo");
+        assert_eq!(person.birthdate, None);
+        assert_eq!(person.favorite_things, vec!["Games"]);
+    }
+
+    #[test]
+    fn builds_many_people_from_the_same_builder() {
+        use builder_non_consuming::Builder;
+
+        let person_builder = PersonBuilder::new()
+            .with_name(String::from("Fulano"))
+            .with_favorite_thing(String::from("Games"));
+
+        let first = person_builder.build().unwrap();
+        let second = person_builder
+            .clone()
+            .with_favorite_thing(String::from("Traveling"))
+            .build()
+            .unwrap();
+
+        assert_eq!(first.favorite_things, vec!["Games"]);
+        assert_eq!(second.favorite_things, vec!["Games", "Traveling"]);
+        assert_eq!(person_builder.build().unwrap(), first);
+    }
+
+    #[test]
+    fn tracks_required_fields_in_the_type() {
+        let builder: PersonBuilder<Unset, Unset> = PersonBuilder::new();
+        let builder: PersonBuilder<Set<String>, Unset> = builder.with_name(String::from("Fulano"));
+        let builder: PersonBuilder<Set<String>, Set<Vec<String>>> = builder.with_favorite_thing(String::from("Games"));
+
+        assert_eq!(builder.name, Set(String::from("Fulano")));
+        assert_eq!(builder.favorite_things.get(), &vec![String::from("Games")]);
+    }
+}
@@ -0,0 +1,38 @@
+// The `PersonBuilder` the `builder_typestate` doctests build with.
+
+use design_patterns_rs::builder_non_consuming;
+use design_patterns_rs::builder_typestate::{Push, Set, Unset};
+use std::convert::Infallible;
+
+struct Person {
+    name: String,
+    favorite_things: Vec<String>,
+}
+
+#[derive(Clone)]
+struct PersonBuilder<N, F> {
+    name: N,
+    favorite_things: F,
+}
+
+impl PersonBuilder<Unset, Unset> {
+    fn new() -> Self {
+        PersonBuilder { name: Unset, favorite_things: Unset }
+    }
+}
+
+impl<N, F: Push<String>> PersonBuilder<N, F> {
+    fn with_name(self, name: &str) -> PersonBuilder<Set<String>, F> {
+        PersonBuilder { name: Set(name.to_string()), favorite_things: self.favorite_things }
+    }
+
+    fn with_favorite_thing(self, thing: &str) -> PersonBuilder<N, Set<Vec<String>>> {
+        PersonBuilder { name: self.name, favorite_things: self.favorite_things.push(thing.to_string()) }
+    }
+}
+
+impl builder_non_consuming::Builder<Person, Infallible> for PersonBuilder<Set<String>, Set<Vec<String>>> {
+    fn build(&self) -> Result<Person, Infallible> {
+        Ok(Person { name: self.name.get().clone(), favorite_things: self.favorite_things.get().clone() })
+    }
+}
@@ -1,3 +1,8 @@
+use crate::items::{Armor, Axe, Bow, EquipableItem, Item};
+use std::convert::Infallible;
+use std::fmt;
+use std::marker::PhantomData;
+
 pub trait Factory {
     type Input;
     type Output;
@@ -5,81 +10,129 @@ pub trait Factory {
     fn create(input: Self::Input) -> Self::Output;
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::any::Any;
+/// A factory whose input may not be turned into an output.
+pub trait TryFactory {
+    type Input;
+    type Output;
+    type Error;
 
-    #[derive(Debug, PartialEq)]
-    enum Usage {
-        MeleeWeapon,
-        RangedWeapon,
-        Protection
+    fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error>;
+}
+
+impl<F: Factory> TryFactory for F {
+    type Input = F::Input;
+    type Output = F::Output;
+    type Error = Infallible;
+
+    fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+        Ok(F::create(input))
     }
+}
 
-    trait EquipableItem: Any {
-        fn get_usage(&self) -> Usage;
-        
-        fn as_any(&self) -> &dyn Any;
+// The adapters below are only ever used as types, like `Fallible::<ItemFactory>::create(..)`.
+
+/// A `Factory` producing the `Result` of a `TryFactory`.
+pub struct Fallible<F>(PhantomData<F>);
+
+impl<F: TryFactory> Factory for Fallible<F> {
+    type Input = F::Input;
+    type Output = Result<F::Output, F::Error>;
+
+    fn create(input: Self::Input) -> Self::Output {
+        F::try_create(input)
     }
-    
-    #[derive(Debug, PartialEq)]
-    enum Item {
-        Bow,
-        Axe,
-        Armor
-    }
-    
-    struct Axe;
-    
-    impl EquipableItem for Axe {
-        fn get_usage(&self) -> Usage {
-            Usage::MeleeWeapon
-        }
+}
+
+/// A `Factory` panicking whenever its `TryFactory` fails, for inputs known to be valid.
+pub struct Unwrapping<F>(PhantomData<F>);
+
+impl<F: TryFactory> Factory for Unwrapping<F>
+where
+    F::Error: fmt::Debug,
+{
+    type Input = F::Input;
+    type Output = F::Output;
 
-        fn as_any(&self) -> &dyn Any {
-            self
+    fn create(input: Self::Input) -> Self::Output {
+        match F::try_create(input) {
+            Ok(output) => output,
+            Err(error) => panic!("factory failed to create its output: {:?}", error),
         }
     }
-    
-    struct Bow;
+}
 
-    impl EquipableItem for Bow {
-        fn get_usage(&self) -> Usage {
-            Usage::RangedWeapon
-        }
+/// A `TryFactory` out of a `Factory` that already returns a `Result`.
+pub struct FromResult<F>(PhantomData<F>);
 
-        fn as_any(&self) -> &dyn Any {
-            self
-        }
+impl<F, O, E> TryFactory for FromResult<F>
+where
+    F: Factory<Output = Result<O, E>>,
+{
+    type Input = F::Input;
+    type Output = O;
+    type Error = E;
+
+    fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+        F::create(input)
     }
+}
 
-    struct Armor;
+/// Creates every kind of `Item` with its standard stats.
+pub struct ItemFactory;
 
-    impl EquipableItem for Armor {
-        fn get_usage(&self) -> Usage {
-            Usage::Protection
-        }
+impl Factory for ItemFactory {
+    type Input = Item;
+    type Output = Box<dyn EquipableItem>;
 
-        fn as_any(&self) -> &dyn Any {
-            self
+    fn create(input: Self::Input) -> Self::Output {
+        match input {
+            Item::Bow => Box::new(Bow::default()),
+            Item::Axe => Box::new(Axe::default()),
+            Item::Armor => Box::new(Armor::default()),
         }
     }
-    
-    struct ItemFactory;
+}
 
-    impl Factory for ItemFactory {
-        type Input = Item;
-        type Output = Box<dyn EquipableItem>;
-  
-        fn create(input: Self::Input) -> Self::Output {
-            match input {
-                Item::Bow => Box::new(Bow {}),  
-                Item::Axe => Box::new(Axe {}),
-                Item::Armor => Box::new(Armor {}),
-            }
-        }
+/// The error of a factory together with the input it failed on.
+#[derive(Debug, PartialEq)]
+pub struct CreationError<I, E> {
+    pub input: I,
+    pub error: E,
+}
+
+impl<I: fmt::Debug, E: fmt::Display> fmt::Display for CreationError<I, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to create from {:?}: {}", self.input, self.error)
+    }
+}
+
+impl<I: fmt::Debug, E: std::error::Error + 'static> std::error::Error for CreationError<I, E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A `TryFactory` whose errors record the input that failed.
+pub struct WithInput<F>(PhantomData<F>);
+
+impl<F: TryFactory> TryFactory for WithInput<F>
+where
+    F::Input: Clone,
+{
+    type Input = F::Input;
+    type Output = F::Output;
+    type Error = CreationError<F::Input, F::Error>;
+
+    fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+        F::try_create(input.clone()).map_err(|error| CreationError { input, error })
     }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+    use crate::items::*;
 
     #[test]
     fn it_creates_bow() {
@@ -113,4 +166,86 @@ mod tests {
         assert_eq!(possibly_an_armor.is_some(), true);
         assert_eq!(possibly_a_bow.is_some(), false);
     }
+
+    #[test]
+    fn it_creates_items_with_attributes() {
+        let bow = ItemFactory::create(Item::Bow);
+        let armor = ItemFactory::create(Item::Armor);
+
+        assert_eq!(bow.attributes().name, "Bow");
+        assert_eq!(bow.attributes().rarity, Rarity::Common);
+        assert_eq!(armor.attributes().name, "Armor");
+        assert_eq!(armor.attributes().defense, 18);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct UnknownItem;
+
+    impl fmt::Display for UnknownItem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "no such item")
+        }
+    }
+
+    impl std::error::Error for UnknownItem {}
+
+    struct ItemNameFactory;
+
+    impl TryFactory for ItemNameFactory {
+        type Input = &'static str;
+        type Output = Box<dyn EquipableItem>;
+        type Error = UnknownItem;
+
+        fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+            match input {
+                "bow" => Ok(ItemFactory::create(Item::Bow)),
+                "axe" => Ok(ItemFactory::create(Item::Axe)),
+                "armor" => Ok(ItemFactory::create(Item::Armor)),
+                _ => Err(UnknownItem),
+            }
+        }
+    }
+
+    #[test]
+    fn infallible_factories_are_try_factories() {
+        let item = match ItemFactory::try_create(Item::Axe) {
+            Ok(item) => item,
+            Err(never) => match never {},
+        };
+
+        assert_eq!(item.get_usage(), Usage::MeleeWeapon);
+    }
+
+    #[test]
+    fn it_fails_creating_unknown_items() {
+        assert_eq!(ItemNameFactory::try_create("bow").unwrap().get_usage(), Usage::RangedWeapon);
+        assert_eq!(ItemNameFactory::try_create("sling").err(), Some(UnknownItem));
+    }
+
+    #[test]
+    fn it_records_the_input_that_failed() {
+        let error = WithInput::<ItemNameFactory>::try_create("sling").err().unwrap();
+
+        assert_eq!(error, CreationError { input: "sling", error: UnknownItem });
+        assert_eq!(error.to_string(), "failed to create from \"sling\": no such item");
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn it_turns_try_factories_into_factories_and_back() {
+        let result = Fallible::<ItemNameFactory>::create("sling");
+        assert_eq!(result.err(), Some(UnknownItem));
+
+        let armor = Unwrapping::<ItemNameFactory>::create("armor");
+        assert_eq!(armor.get_usage(), Usage::Protection);
+
+        let bow = FromResult::<Fallible<ItemNameFactory>>::try_create("bow");
+        assert_eq!(bow.unwrap().get_usage(), Usage::RangedWeapon);
+    }
+
+    #[test]
+    #[should_panic(expected = "factory failed to create its output: UnknownItem")]
+    fn unwrapping_panics_on_invalid_input() {
+        Unwrapping::<ItemNameFactory>::create("sling");
+    }
 }
@@ -0,0 +1,242 @@
+// `Factory::create` takes no `self`, so combinators are composed as types and
+// aliased, e.g. `type DurableItemFactory = Map<ItemFactory, AttachDurability>;`.
+// `Map`, `Contramap` and `Zip` combine infallible `Factory`s; `AndThen` and
+// `OrElse` combine `TryFactory`s, which every `Factory` already is.
+
+use crate::factory::{Factory, TryFactory};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Feeds the output of `F` into `G`.
+pub struct Map<F, G>(PhantomData<(F, G)>);
+
+impl<F, G> Factory for Map<F, G>
+where
+    F: Factory,
+    G: Factory<Input = F::Output>,
+{
+    type Input = F::Input;
+    type Output = G::Output;
+
+    fn create(input: Self::Input) -> Self::Output {
+        G::create(F::create(input))
+    }
+}
+
+/// Prepares the input of `F` with `G`, so `F` can be fed `G`'s input instead.
+pub struct Contramap<F, G>(PhantomData<(F, G)>);
+
+impl<F, G> Factory for Contramap<F, G>
+where
+    F: Factory,
+    G: Factory<Output = F::Input>,
+{
+    type Input = G::Input;
+    type Output = F::Output;
+
+    fn create(input: Self::Input) -> Self::Output {
+        F::create(G::create(input))
+    }
+}
+
+/// Creates with both factories at once, from a pair of inputs.
+pub struct Zip<F, G>(PhantomData<(F, G)>);
+
+impl<F: Factory, G: Factory> Factory for Zip<F, G> {
+    type Input = (F::Input, G::Input);
+    type Output = (F::Output, G::Output);
+
+    fn create((first, second): Self::Input) -> Self::Output {
+        (F::create(first), G::create(second))
+    }
+}
+
+/// The error of whichever factory in an `AndThen` chain failed.
+#[derive(Debug, PartialEq)]
+pub enum AndThenError<A, B> {
+    First(A),
+    Second(B),
+}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for AndThenError<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AndThenError::First(error) => error.fmt(f),
+            AndThenError::Second(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<A: fmt::Debug + fmt::Display, B: fmt::Debug + fmt::Display> std::error::Error for AndThenError<A, B> {}
+
+/// Feeds the output of `F` into `G`, stopping at the first failure.
+pub struct AndThen<F, G>(PhantomData<(F, G)>);
+
+impl<F, G> TryFactory for AndThen<F, G>
+where
+    F: TryFactory,
+    G: TryFactory<Input = F::Output>,
+{
+    type Input = F::Input;
+    type Output = G::Output;
+    type Error = AndThenError<F::Error, G::Error>;
+
+    fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+        let intermediate = F::try_create(input).map_err(AndThenError::First)?;
+        G::try_create(intermediate).map_err(AndThenError::Second)
+    }
+}
+
+/// Falls back to `G` whenever `F` fails on an input.
+pub struct OrElse<F, G>(PhantomData<(F, G)>);
+
+impl<F, G> TryFactory for OrElse<F, G>
+where
+    F: TryFactory,
+    F::Input: Clone,
+    G: TryFactory<Input = F::Input, Output = F::Output>,
+{
+    type Input = F::Input;
+    type Output = F::Output;
+    type Error = G::Error;
+
+    fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+        match F::try_create(input.clone()) {
+            Ok(output) => Ok(output),
+            Err(_) => G::try_create(input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    enum Item {
+        Bow,
+        Axe,
+        Armor
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Equipment {
+        item: Item,
+        weight: u32,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Durable<T> {
+        item: T,
+        durability: u32,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct UnknownItem;
+
+    struct ItemFactory;
+
+    impl Factory for ItemFactory {
+        type Input = Item;
+        type Output = Equipment;
+
+        fn create(input: Self::Input) -> Self::Output {
+            let weight = match input {
+                Item::Bow => 2,
+                Item::Axe => 5,
+                Item::Armor => 12,
+            };
+            Equipment { item: input, weight }
+        }
+    }
+
+    struct AttachDurability;
+
+    impl Factory for AttachDurability {
+        type Input = Equipment;
+        type Output = Durable<Equipment>;
+
+        fn create(input: Self::Input) -> Self::Output {
+            Durable { durability: input.weight * 10, item: input }
+        }
+    }
+
+    struct ParseItem;
+
+    impl TryFactory for ParseItem {
+        type Input = &'static str;
+        type Output = Item;
+        type Error = UnknownItem;
+
+        fn try_create(input: Self::Input) -> Result<Self::Output, Self::Error> {
+            match input {
+                "bow" => Ok(Item::Bow),
+                "axe" => Ok(Item::Axe),
+                "armor" => Ok(Item::Armor),
+                _ => Err(UnknownItem),
+            }
+        }
+    }
+
+    struct DefaultItem;
+
+    impl Factory for DefaultItem {
+        type Input = &'static str;
+        type Output = Item;
+
+        fn create(_: Self::Input) -> Self::Output {
+            Item::Axe
+        }
+    }
+
+    struct Heaviest;
+
+    impl Factory for Heaviest {
+        type Input = ();
+        type Output = Item;
+
+        fn create(_: Self::Input) -> Self::Output {
+            Item::Armor
+        }
+    }
+
+    type DurableItemFactory = Map<ItemFactory, AttachDurability>;
+
+    #[test]
+    fn it_maps_over_the_output() {
+        let bow = DurableItemFactory::create(Item::Bow);
+
+        assert_eq!(bow, Durable { item: Equipment { item: Item::Bow, weight: 2 }, durability: 20 });
+    }
+
+    #[test]
+    fn it_maps_over_the_input() {
+        let armor = Contramap::<ItemFactory, Heaviest>::create(());
+
+        assert_eq!(armor.item, Item::Armor);
+    }
+
+    #[test]
+    fn it_chains_fallible_factories() {
+        type FromName = AndThen<ParseItem, DurableItemFactory>;
+
+        assert_eq!(FromName::try_create("axe").unwrap().durability, 50);
+        assert_eq!(FromName::try_create("sling").err(), Some(AndThenError::First(UnknownItem)));
+    }
+
+    #[test]
+    fn it_falls_back_to_another_factory() {
+        type FromNameOrAxe = OrElse<ParseItem, DefaultItem>;
+
+        assert_eq!(FromNameOrAxe::try_create("bow"), Ok(Item::Bow));
+        assert_eq!(FromNameOrAxe::try_create("sling"), Ok(Item::Axe));
+    }
+
+    #[test]
+    fn it_creates_from_two_factories_at_once() {
+        let (weapon, armor) = Zip::<ItemFactory, DurableItemFactory>::create((Item::Axe, Item::Armor));
+
+        assert_eq!(weapon.item, Item::Axe);
+        assert_eq!(armor.durability, 120);
+    }
+}
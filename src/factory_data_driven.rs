@@ -0,0 +1,311 @@
+// An item factory reading its items from data instead of a hard-coded `match`.
+// Designers describe each item as a template with an ID, the kind of item and its
+// attributes, in JSON, TOML or RON depending on the enabled cargo features. Every
+// template is checked against the item model when loading, and `create` builds
+// items from them by ID.
+//
+// All three formats hold a list of templates under `items`, e.g. in TOML:
+//
+//     [[items]]
+//     id = "elven_bow"
+//     kind = "Bow"
+//     name = "Elven Bow"
+//     weight = 0.8
+//     durability = 150
+//     damage = 15
+//     rarity = "Epic"
+
+use crate::factory_stateful::InstanceFactory;
+use crate::items::{Armor, Axe, Bow, EquipableItem, Item, ItemAttributes, Rarity, Usage};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ItemTemplate {
+    pub id: String,
+    pub kind: Item,
+    pub name: String,
+    /// In kilograms.
+    pub weight: f32,
+    pub durability: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub damage: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub defense: u32,
+    #[cfg_attr(feature = "serde", serde(default = "common"))]
+    pub rarity: Rarity,
+}
+
+#[cfg(feature = "serde")]
+fn common() -> Rarity {
+    Rarity::Common
+}
+
+impl ItemTemplate {
+    pub fn attributes(&self) -> ItemAttributes {
+        ItemAttributes {
+            name: self.name.clone(),
+            weight: self.weight,
+            durability: self.durability,
+            damage: self.damage,
+            defense: self.defense,
+            rarity: self.rarity,
+        }
+    }
+
+    /// Why the template can't describe an item of its kind, if it can't.
+    fn problem(&self) -> Option<&'static str> {
+        let is_protection = self.kind.usage() == Usage::Protection;
+        if self.id.is_empty() {
+            Some("the ID is empty")
+        } else if self.name.is_empty() {
+            Some("the name is empty")
+        } else if !(self.weight.is_finite() && self.weight > 0.0) {
+            Some("the weight must be a positive number of kilograms")
+        } else if self.durability == 0 {
+            Some("the durability must be positive")
+        } else if is_protection && (self.damage > 0 || self.defense == 0) {
+            Some("protection must have defense and no damage")
+        } else if !is_protection && (self.damage == 0 || self.defense > 0) {
+            Some("weapons must have damage and no defense")
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemDataError {
+    /// The data isn't valid `format`, or doesn't hold item templates.
+    Parse {
+        format: &'static str,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    InvalidTemplate { id: String, problem: &'static str },
+    DuplicateId(String),
+    UnknownId(String),
+}
+
+impl fmt::Display for ItemDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemDataError::Parse { format, line, column, message } => {
+                write!(f, "{} error at line {}, column {}: {}", format, line, column, message)
+            }
+            ItemDataError::InvalidTemplate { id, problem } => write!(f, "invalid template {:?}: {}", id, problem),
+            ItemDataError::DuplicateId(id) => write!(f, "more than one template has the ID {:?}", id),
+            ItemDataError::UnknownId(id) => write!(f, "no template has the ID {:?}", id),
+        }
+    }
+}
+
+impl std::error::Error for ItemDataError {}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemData {
+    items: Vec<ItemTemplate>,
+}
+
+/// Creates items from templates loaded from data, by template ID.
+#[derive(Debug, Clone, Default)]
+pub struct DataDrivenItemFactory {
+    templates: HashMap<String, ItemTemplate>,
+}
+
+impl DataDrivenItemFactory {
+    /// Refuses the templates if any of them is invalid or shares its ID.
+    pub fn from_templates<I>(templates: I) -> Result<Self, ItemDataError>
+    where
+        I: IntoIterator<Item = ItemTemplate>,
+    {
+        let mut factory = Self::default();
+        for template in templates {
+            if let Some(problem) = template.problem() {
+                return Err(ItemDataError::InvalidTemplate { id: template.id, problem });
+            }
+            if factory.templates.contains_key(&template.id) {
+                return Err(ItemDataError::DuplicateId(template.id));
+            }
+            factory.templates.insert(template.id.clone(), template);
+        }
+        Ok(factory)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(source: &str) -> Result<Self, ItemDataError> {
+        let data: ItemData = serde_json::from_str(source).map_err(|error| {
+            // The message ends with the position, which is reported on its own.
+            let message = error.to_string();
+            let position = format!(" at line {} column {}", error.line(), error.column());
+            ItemDataError::Parse {
+                format: "JSON",
+                line: error.line(),
+                column: error.column(),
+                message: message.trim_end_matches(&position).to_string(),
+            }
+        })?;
+        Self::from_templates(data.items)
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(source: &str) -> Result<Self, ItemDataError> {
+        let data: ItemData = toml::from_str(source).map_err(|error| {
+            let offset = error.span().map_or(0, |span| span.start);
+            let before = &source[..offset];
+            ItemDataError::Parse {
+                format: "TOML",
+                line: before.matches('\n').count() + 1,
+                column: before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+                message: error.message().to_string(),
+            }
+        })?;
+        Self::from_templates(data.items)
+    }
+
+    #[cfg(feature = "ron")]
+    pub fn from_ron(source: &str) -> Result<Self, ItemDataError> {
+        let data: ItemData = ron::from_str(source).map_err(|error| ItemDataError::Parse {
+            format: "RON",
+            line: error.position.line,
+            column: error.position.col,
+            message: error.code.to_string(),
+        })?;
+        Self::from_templates(data.items)
+    }
+
+    pub fn template(&self, id: &str) -> Option<&ItemTemplate> {
+        self.templates.get(id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(String::as_str)
+    }
+}
+
+impl InstanceFactory for DataDrivenItemFactory {
+    type Input = String;
+    type Output = Result<Box<dyn EquipableItem>, ItemDataError>;
+
+    fn create(&self, id: Self::Input) -> Self::Output {
+        let template = self.template(&id).ok_or(ItemDataError::UnknownId(id))?;
+        let attributes = template.attributes();
+        Ok(match template.kind {
+            Item::Bow => Box::new(Bow::new(attributes)),
+            Item::Axe => Box::new(Axe::new(attributes)),
+            Item::Armor => Box::new(Armor::new(attributes)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elven_bow() -> ItemTemplate {
+        ItemTemplate {
+            id: String::from("elven_bow"),
+            kind: Item::Bow,
+            name: String::from("Elven Bow"),
+            weight: 0.8,
+            durability: 150,
+            damage: 15,
+            defense: 0,
+            rarity: Rarity::Epic,
+        }
+    }
+
+    #[test]
+    fn creates_items_by_id() {
+        let factory = DataDrivenItemFactory::from_templates(vec![elven_bow()]).unwrap();
+
+        let item = factory.create(String::from("elven_bow")).unwrap();
+
+        assert_eq!(item.get_usage(), Usage::RangedWeapon);
+        assert_eq!(*item.attributes(), elven_bow().attributes());
+        assert!(item.as_any().downcast_ref::<Bow>().is_some());
+        assert_eq!(
+            factory.create(String::from("war_axe")).err(),
+            Some(ItemDataError::UnknownId(String::from("war_axe")))
+        );
+    }
+
+    #[test]
+    fn validates_templates_against_the_item_model() {
+        let armor_with_damage = ItemTemplate { kind: Item::Armor, defense: 10, ..elven_bow() };
+        let weightless = ItemTemplate { weight: 0.0, ..elven_bow() };
+
+        assert_eq!(
+            DataDrivenItemFactory::from_templates(vec![armor_with_damage]).err(),
+            Some(ItemDataError::InvalidTemplate {
+                id: String::from("elven_bow"),
+                problem: "protection must have defense and no damage"
+            })
+        );
+        assert!(DataDrivenItemFactory::from_templates(vec![weightless]).is_err());
+        assert_eq!(
+            DataDrivenItemFactory::from_templates(vec![elven_bow(), elven_bow()]).err(),
+            Some(ItemDataError::DuplicateId(String::from("elven_bow")))
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn loads_json() {
+        let factory = DataDrivenItemFactory::from_json(
+            r#"{ "items": [
+                { "id": "elven_bow", "kind": "Bow", "name": "Elven Bow", "weight": 0.8,
+                  "durability": 150, "damage": 15, "rarity": "Epic" }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(factory.template("elven_bow"), Some(&elven_bow()));
+
+        let error = DataDrivenItemFactory::from_json("{ \"items\": [\n  { \"id\": 3 }\n] }").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "JSON error at line 2, column 11: invalid type: integer `3`, expected a string"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn loads_toml() {
+        let factory = DataDrivenItemFactory::from_toml(
+            "[[items]]\nid = \"elven_bow\"\nkind = \"Bow\"\nname = \"Elven Bow\"\n\
+             weight = 0.8\ndurability = 150\ndamage = 15\nrarity = \"Epic\"\n",
+        )
+        .unwrap();
+        assert_eq!(factory.template("elven_bow"), Some(&elven_bow()));
+
+        let error = DataDrivenItemFactory::from_toml("[[items]]\nid = \"axe\"\nkind = \"Sword\"\n").err().unwrap();
+        match error {
+            ItemDataError::Parse { format, line, column, .. } => assert_eq!((format, line, column), ("TOML", 3, 8)),
+            error => panic!("expected a parse error, got {:?}", error),
+        }
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn loads_ron() {
+        let factory = DataDrivenItemFactory::from_ron(
+            r#"(items: [
+                (id: "elven_bow", kind: Bow, name: "Elven Bow", weight: 0.8,
+                 durability: 150, damage: 15, rarity: Epic),
+            ])"#,
+        )
+        .unwrap();
+        assert_eq!(factory.template("elven_bow"), Some(&elven_bow()));
+
+        let error = DataDrivenItemFactory::from_ron("(items: [\n  (id: \"axe\", colour: Red),\n])").err().unwrap();
+        match error {
+            ItemDataError::Parse { format, line, .. } => assert_eq!((format, line), ("RON", 2)),
+            error => panic!("expected a parse error, got {:?}", error),
+        }
+    }
+}
@@ -1,55 +1,26 @@
-#[cfg(test)]
-mod tests {
-    #[derive(Debug, PartialEq)]
-    enum Usage {
-        MeleeWeapon,
-        RangedWeapon,
-        Protection
-    }
+use crate::items::{Armor, Axe, Bow};
 
-    trait EquipableItem {
-        fn get_usage(&self) -> Usage;
-    }
+/// Creates items as their concrete types, so callers need no downcasting.
+pub struct ItemFactory;
 
-    struct Axe;
-    
-    impl EquipableItem for Axe {
-        fn get_usage(&self) -> Usage {
-            Usage::MeleeWeapon
-        }
+impl ItemFactory {
+    pub fn create_axe() -> Axe {
+        Axe::default()
     }
-    
-    struct Bow;
 
-    impl EquipableItem for Bow {
-        fn get_usage(&self) -> Usage {
-            Usage::RangedWeapon
-        }
+    pub fn create_bow() -> Bow {
+        Bow::default()
     }
 
-    struct Armor;
-
-    impl EquipableItem for Armor {
-        fn get_usage(&self) -> Usage {
-            Usage::Protection
-        }
+    pub fn create_armor() -> Armor {
+        Armor::default()
     }
-    
-    struct ItemFactory;
-
-    impl ItemFactory {
-        fn create_axe() -> Axe {
-            Axe {}
-        }
-
-        fn create_bow() -> Bow {
-            Bow {}
-        }
+}
 
-        fn create_armor() -> Armor {
-            Armor {}
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::{EquipableItem, Usage};
 
     #[test]
     fn it_creates_bow() {
@@ -71,4 +42,15 @@ mod tests {
 
         assert_eq!(armor.get_usage(), Usage::Protection);
     }
+
+    #[test]
+    fn it_creates_items_with_attributes() {
+        let axe = ItemFactory::create_axe();
+        let armor = ItemFactory::create_armor();
+
+        assert_eq!(axe.attributes().name, "Axe");
+        assert_eq!(axe.attributes().damage, 14);
+        assert_eq!(armor.attributes().name, "Armor");
+        assert_eq!(armor.attributes().defense, 18);
+    }
 }
@@ -0,0 +1,196 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+type Constructor<T> = Box<dyn Fn() -> T>;
+
+#[derive(Debug, PartialEq)]
+pub enum FactoryRegistryError<K> {
+    UnknownKey(K),
+    AlreadyRegistered(K),
+}
+
+impl<K: fmt::Debug> fmt::Display for FactoryRegistryError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FactoryRegistryError::UnknownKey(key) => write!(f, "no constructor is registered under {:?}", key),
+            FactoryRegistryError::AlreadyRegistered(key) => write!(f, "a constructor is already registered under {:?}", key),
+        }
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for FactoryRegistryError<K> {}
+
+/// A factory whose constructors are registered at runtime under a key, instead of
+/// being hard-coded in a `match` like `ItemFactory`.
+pub struct FactoryRegistry<K, T> {
+    constructors: HashMap<K, Constructor<T>>,
+}
+
+impl<K: Eq + Hash + Clone, T> FactoryRegistry<K, T> {
+    pub fn new() -> Self {
+        Self {
+            constructors: HashMap::new(),
+        }
+    }
+
+    /// Registers a constructor, refusing to replace one already under `key`.
+    pub fn register<F>(&mut self, key: K, constructor: F) -> Result<(), FactoryRegistryError<K>>
+    where
+        F: Fn() -> T + 'static,
+    {
+        if self.constructors.contains_key(&key) {
+            return Err(FactoryRegistryError::AlreadyRegistered(key));
+        }
+
+        self.constructors.insert(key, Box::new(constructor));
+        Ok(())
+    }
+
+    /// Registers a constructor, overriding any entry already under `key`.
+    /// Returns whether an entry was overridden.
+    pub fn register_or_replace<F>(&mut self, key: K, constructor: F) -> bool
+    where
+        F: Fn() -> T + 'static,
+    {
+        self.constructors.insert(key, Box::new(constructor)).is_some()
+    }
+
+    /// Like `HashMap::remove`, `key` may be any borrowed form of `K`, e.g. a `&str`
+    /// for `String` keys.
+    pub fn unregister<Q>(&mut self, key: &Q) -> Result<(), FactoryRegistryError<K>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + ToOwned<Owned = K>,
+    {
+        match self.constructors.remove(key) {
+            Some(_) => Ok(()),
+            None => Err(FactoryRegistryError::UnknownKey(key.to_owned())),
+        }
+    }
+
+    /// Like `HashMap::get`, `key` may be any borrowed form of `K`.
+    pub fn create<Q>(&self, key: &Q) -> Result<T, FactoryRegistryError<K>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + ToOwned<Owned = K>,
+    {
+        match self.constructors.get(key) {
+            Some(constructor) => Ok(constructor()),
+            None => Err(FactoryRegistryError::UnknownKey(key.to_owned())),
+        }
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.constructors.contains_key(key)
+    }
+
+    /// The registered keys, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.constructors.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.constructors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constructors.is_empty()
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> Default for FactoryRegistry<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::*;
+
+    fn item_registry() -> FactoryRegistry<Item, Box<dyn EquipableItem>> {
+        let mut registry: FactoryRegistry<Item, Box<dyn EquipableItem>> = FactoryRegistry::new();
+
+        registry.register(Item::Bow, || Box::new(Bow::default())).unwrap();
+        registry.register(Item::Axe, || Box::new(Axe::default())).unwrap();
+        registry
+    }
+
+    #[test]
+    fn it_creates_registered_items() {
+        let registry = item_registry();
+
+        let item = registry.create(&Item::Bow).unwrap();
+
+        assert_eq!(item.get_usage(), Usage::RangedWeapon);
+        assert!(item.as_any().downcast_ref::<Bow>().is_some());
+    }
+
+    #[test]
+    fn it_fails_on_unknown_keys() {
+        let registry = item_registry();
+
+        let error = registry.create(&Item::Armor).err().unwrap();
+
+        assert_eq!(error, FactoryRegistryError::UnknownKey(Item::Armor));
+        assert_eq!(error.to_string(), "no constructor is registered under Armor");
+    }
+
+    #[test]
+    fn it_registers_and_unregisters_at_runtime() {
+        let mut registry = item_registry();
+
+        registry.register(Item::Armor, || Box::new(Armor::default())).unwrap();
+        assert_eq!(registry.create(&Item::Armor).unwrap().get_usage(), Usage::Protection);
+
+        registry.unregister(&Item::Armor).unwrap();
+        assert!(!registry.contains(&Item::Armor));
+        assert_eq!(registry.unregister(&Item::Armor), Err(FactoryRegistryError::UnknownKey(Item::Armor)));
+    }
+
+    #[test]
+    fn it_only_overrides_entries_when_asked_to() {
+        let mut registry = item_registry();
+
+        let duplicate = registry.register(Item::Bow, || Box::new(Axe::default()));
+        assert_eq!(duplicate, Err(FactoryRegistryError::AlreadyRegistered(Item::Bow)));
+        assert!(registry.create(&Item::Bow).unwrap().as_any().downcast_ref::<Bow>().is_some());
+
+        assert!(registry.register_or_replace(Item::Bow, || Box::new(Axe::default())));
+        assert!(registry.create(&Item::Bow).unwrap().as_any().downcast_ref::<Axe>().is_some());
+    }
+
+    #[test]
+    fn it_lists_registered_keys() {
+        let mut registry: FactoryRegistry<String, u32> = FactoryRegistry::new();
+
+        registry.register(String::from("sling"), || 1).unwrap();
+        registry.register(String::from("blowgun"), || 2).unwrap();
+
+        let mut keys: Vec<&String> = registry.keys().collect();
+        keys.sort();
+
+        assert_eq!(keys, vec!["blowgun", "sling"]);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn it_looks_up_string_keys_by_str() {
+        let mut registry: FactoryRegistry<String, u32> = FactoryRegistry::new();
+
+        registry.register(String::from("sling"), || 1).unwrap();
+
+        assert_eq!(registry.create("sling"), Ok(1));
+        assert!(registry.contains("sling"));
+        assert_eq!(registry.create("blowgun"), Err(FactoryRegistryError::UnknownKey(String::from("blowgun"))));
+        registry.unregister("sling").unwrap();
+        assert!(registry.is_empty());
+    }
+}
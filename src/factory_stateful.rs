@@ -0,0 +1,167 @@
+// Factories that hold configuration or state, such as an RNG seed, an ID counter
+// or a reference to an asset cache. Every static `Factory` is an `InstanceFactory`,
+// and every `InstanceFactory` is an `InstanceFactoryMut`, so code written against
+// the most general trait accepts all three kinds.
+
+use crate::factory::Factory;
+
+/// A factory creating from shared state.
+pub trait InstanceFactory {
+    type Input;
+    type Output;
+
+    fn create(&self, input: Self::Input) -> Self::Output;
+}
+
+/// A factory that may update its state on every creation.
+pub trait InstanceFactoryMut {
+    type Input;
+    type Output;
+
+    fn create(&mut self, input: Self::Input) -> Self::Output;
+}
+
+impl<F: Factory> InstanceFactory for F {
+    type Input = F::Input;
+    type Output = F::Output;
+
+    fn create(&self, input: Self::Input) -> Self::Output {
+        F::create(input)
+    }
+}
+
+impl<F: InstanceFactory> InstanceFactoryMut for F {
+    type Input = F::Input;
+    type Output = F::Output;
+
+    fn create(&mut self, input: Self::Input) -> Self::Output {
+        InstanceFactory::create(self, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    enum Item {
+        Bow,
+        Axe,
+        Armor
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Equipment {
+        id: u64,
+        item: Item,
+        quality: u32,
+    }
+
+    struct ItemFactory;
+
+    impl Factory for ItemFactory {
+        type Input = Item;
+        type Output = Equipment;
+
+        fn create(input: Self::Input) -> Self::Output {
+            Equipment { id: 0, item: input, quality: 1 }
+        }
+    }
+
+    struct UniqueItemFactory {
+        next_id: u64,
+    }
+
+    impl InstanceFactoryMut for UniqueItemFactory {
+        type Input = Item;
+        type Output = Equipment;
+
+        fn create(&mut self, input: Self::Input) -> Self::Output {
+            self.next_id += 1;
+            Equipment { id: self.next_id, item: input, quality: 1 }
+        }
+    }
+
+    struct LootFactory {
+        state: u64,
+    }
+
+    impl LootFactory {
+        fn with_seed(seed: u64) -> Self {
+            Self { state: seed.max(1) }
+        }
+    }
+
+    impl InstanceFactoryMut for LootFactory {
+        type Input = Item;
+        type Output = Equipment;
+
+        fn create(&mut self, input: Self::Input) -> Self::Output {
+            // xorshift64
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            Equipment { id: 0, item: input, quality: (self.state % 5) as u32 + 1 }
+        }
+    }
+
+    struct QualityTable {
+        qualities: HashMap<Item, u32>,
+    }
+
+    struct CatalogFactory<'a> {
+        table: &'a QualityTable,
+    }
+
+    impl<'a> InstanceFactory for CatalogFactory<'a> {
+        type Input = Item;
+        type Output = Equipment;
+
+        fn create(&self, input: Self::Input) -> Self::Output {
+            Equipment { id: 0, item: input, quality: self.table.qualities[&input] }
+        }
+    }
+
+    fn stock<F: InstanceFactoryMut<Input = Item, Output = Equipment>>(factory: &mut F) -> Vec<Equipment> {
+        vec![factory.create(Item::Bow), factory.create(Item::Axe), factory.create(Item::Armor)]
+    }
+
+    #[test]
+    fn it_keeps_state_between_creations() {
+        let mut factory = UniqueItemFactory { next_id: 0 };
+
+        let ids: Vec<u64> = stock(&mut factory).iter().map(|equipment| equipment.id).collect();
+
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(factory.create(Item::Bow).id, 4);
+    }
+
+    #[test]
+    fn it_is_deterministic_for_a_seed() {
+        let first = stock(&mut LootFactory::with_seed(42));
+        let second = stock(&mut LootFactory::with_seed(42));
+
+        assert_eq!(first, second);
+        assert!(first.iter().all(|equipment| (1..=5).contains(&equipment.quality)));
+    }
+
+    #[test]
+    fn it_creates_from_borrowed_configuration() {
+        let table = QualityTable {
+            qualities: vec![(Item::Bow, 3), (Item::Axe, 4), (Item::Armor, 5)].into_iter().collect(),
+        };
+        let factory = CatalogFactory { table: &table };
+
+        assert_eq!(factory.create(Item::Axe).quality, 4);
+        assert_eq!(stock(&mut CatalogFactory { table: &table })[2].quality, 5);
+    }
+
+    #[test]
+    fn static_factories_keep_working() {
+        let equipment = stock(&mut ItemFactory);
+
+        assert_eq!(equipment[0], <ItemFactory as Factory>::create(Item::Bow));
+        assert_eq!(InstanceFactory::create(&ItemFactory, Item::Armor).item, Item::Armor);
+    }
+}
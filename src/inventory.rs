@@ -0,0 +1,322 @@
+// Somewhere for the items factories make to go. An `Inventory` holds items in a
+// limited number of slots, each holding a stack of equal items up to the item's
+// `max_stack`, and refuses items that would exceed its weight limit. Equipped items
+// sit in one slot per `Usage` and still count towards the carried weight.
+
+use crate::items::{EquipableItem, Usage};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InventoryError {
+    Full { capacity: usize },
+    TooHeavy { weight: f32, max_weight: f32 },
+    NoSuchStack(usize),
+    IncompatibleSlot { slot: Usage, usage: Usage },
+    SlotOccupied(Usage),
+    EmptySlot(Usage),
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::Full { capacity } => write!(f, "all {} inventory slots are taken", capacity),
+            InventoryError::TooHeavy { weight, max_weight } => {
+                write!(f, "carrying {} kg would exceed the limit of {} kg", weight, max_weight)
+            }
+            InventoryError::NoSuchStack(index) => write!(f, "there is no stack at index {}", index),
+            InventoryError::IncompatibleSlot { slot, usage } => {
+                write!(f, "a {:?} item cannot be equipped in the {:?} slot", usage, slot)
+            }
+            InventoryError::SlotOccupied(slot) => write!(f, "the {:?} slot is already taken", slot),
+            InventoryError::EmptySlot(slot) => write!(f, "nothing is equipped in the {:?} slot", slot),
+        }
+    }
+}
+
+impl std::error::Error for InventoryError {}
+
+/// An item the inventory refused, handed back so it isn't lost.
+pub struct Rejected {
+    pub error: InventoryError,
+    pub item: Box<dyn EquipableItem>,
+}
+
+impl fmt::Debug for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rejected")
+            .field("error", &self.error)
+            .field("item", &self.item.attributes().name)
+            .finish()
+    }
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} was refused: {}", self.item.attributes().name, self.error)
+    }
+}
+
+impl std::error::Error for Rejected {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Equal items sharing an inventory slot.
+pub struct Stack {
+    items: Vec<Box<dyn EquipableItem>>,
+}
+
+impl Stack {
+    pub fn item(&self) -> &dyn EquipableItem {
+        self.items[0].as_ref()
+    }
+
+    pub fn count(&self) -> usize {
+        self.items.len()
+    }
+
+    fn weight(&self) -> f32 {
+        self.item().attributes().weight * self.count() as f32
+    }
+
+    fn accepts(&self, item: &dyn EquipableItem) -> bool {
+        let top = self.item();
+        self.count() < top.max_stack() as usize
+            && top.as_any().type_id() == item.as_any().type_id()
+            && top.attributes() == item.attributes()
+    }
+}
+
+pub struct Inventory {
+    capacity: usize,
+    /// In kilograms.
+    max_weight: f32,
+    stacks: Vec<Stack>,
+    equipped: HashMap<Usage, Box<dyn EquipableItem>>,
+}
+
+impl Inventory {
+    /// An empty inventory of `capacity` slots carrying at most `max_weight` kg.
+    pub fn new(capacity: usize, max_weight: f32) -> Self {
+        Self {
+            capacity,
+            max_weight,
+            stacks: Vec::new(),
+            equipped: HashMap::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn max_weight(&self) -> f32 {
+        self.max_weight
+    }
+
+    /// The weight of every item, equipped or not.
+    pub fn weight(&self) -> f32 {
+        let stacked: f32 = self.stacks.iter().map(Stack::weight).sum();
+        let equipped: f32 = self.equipped.values().map(|item| item.attributes().weight).sum();
+        stacked + equipped
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    /// The stacks of items with `usage`, with their index.
+    pub fn with_usage(&self, usage: Usage) -> impl Iterator<Item = (usize, &Stack)> {
+        self.stacks
+            .iter()
+            .enumerate()
+            .filter(move |(_, stack)| stack.item().get_usage() == usage)
+    }
+
+    /// Adds `item` to a stack of equal items with room left, or to a free slot.
+    pub fn add(&mut self, item: Box<dyn EquipableItem>) -> Result<(), Rejected> {
+        let weight = self.weight() + item.attributes().weight;
+        if weight > self.max_weight {
+            let error = InventoryError::TooHeavy { weight, max_weight: self.max_weight };
+            return Err(Rejected { error, item });
+        }
+
+        if let Some(stack) = self.stacks.iter_mut().find(|stack| stack.accepts(item.as_ref())) {
+            stack.items.push(item);
+        } else if self.stacks.len() < self.capacity {
+            self.stacks.push(Stack { items: vec![item] });
+        } else {
+            let error = InventoryError::Full { capacity: self.capacity };
+            return Err(Rejected { error, item });
+        }
+        Ok(())
+    }
+
+    /// Takes one item off the stack at `index`, freeing its slot once empty.
+    pub fn remove(&mut self, index: usize) -> Result<Box<dyn EquipableItem>, InventoryError> {
+        let stack = self.stacks.get_mut(index).ok_or(InventoryError::NoSuchStack(index))?;
+        let item = stack.items.pop().expect("stacks are never empty");
+        if stack.items.is_empty() {
+            self.stacks.remove(index);
+        }
+        Ok(item)
+    }
+
+    pub fn equipped(&self, slot: Usage) -> Option<&dyn EquipableItem> {
+        self.equipped.get(&slot).map(|item| item.as_ref())
+    }
+
+    /// Moves one item from the stack at `index` into `slot`.
+    pub fn equip(&mut self, index: usize, slot: Usage) -> Result<(), InventoryError> {
+        let stack = self.stacks.get(index).ok_or(InventoryError::NoSuchStack(index))?;
+        let usage = stack.item().get_usage();
+        if usage != slot {
+            return Err(InventoryError::IncompatibleSlot { slot, usage });
+        }
+        if self.equipped.contains_key(&slot) {
+            return Err(InventoryError::SlotOccupied(slot));
+        }
+
+        let item = self.remove(index)?;
+        self.equipped.insert(slot, item);
+        Ok(())
+    }
+
+    /// Moves the item in `slot` back into the inventory, leaving it equipped if
+    /// there is no room for it.
+    pub fn unequip(&mut self, slot: Usage) -> Result<(), InventoryError> {
+        let item = self.equipped.remove(&slot).ok_or(InventoryError::EmptySlot(slot))?;
+        self.add(item).map_err(|rejected| {
+            self.equipped.insert(slot, rejected.item);
+            rejected.error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::*;
+    use std::any::Any;
+
+    struct Arrow(ItemAttributes);
+
+    impl Arrow {
+        fn new() -> Self {
+            Self(ItemAttributes {
+                name: String::from("Arrow"),
+                weight: 0.05,
+                durability: 1,
+                damage: 4,
+                defense: 0,
+                rarity: Rarity::Common,
+            })
+        }
+    }
+
+    impl EquipableItem for Arrow {
+        fn get_usage(&self) -> Usage {
+            Usage::RangedWeapon
+        }
+
+        fn attributes(&self) -> &ItemAttributes {
+            &self.0
+        }
+
+        fn max_stack(&self) -> u32 {
+            20
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn stacks_equal_stackable_items() {
+        let mut inventory = Inventory::new(3, 50.0);
+
+        for _ in 0..25 {
+            inventory.add(Box::new(Arrow::new())).unwrap();
+        }
+        inventory.add(Box::new(Bow::default())).unwrap();
+
+        let counts: Vec<usize> = inventory.stacks().iter().map(Stack::count).collect();
+        assert_eq!(counts, vec![20, 5, 1]);
+
+        let error = inventory.add(Box::new(Bow::default())).err().unwrap();
+        assert_eq!(error.error, InventoryError::Full { capacity: 3 });
+        assert_eq!(error.item.attributes().name, "Bow");
+
+        inventory.add(Box::new(Arrow::new())).unwrap();
+        assert_eq!(inventory.stacks()[1].count(), 6);
+    }
+
+    #[test]
+    fn refuses_items_over_the_weight_limit() {
+        let mut inventory = Inventory::new(10, 15.0);
+        inventory.add(Box::new(Armor::default())).unwrap();
+        inventory.equip(0, Usage::Protection).unwrap();
+
+        let error = inventory.add(Box::new(Axe::default())).err().unwrap();
+
+        assert_eq!(error.error, InventoryError::TooHeavy { weight: 15.5, max_weight: 15.0 });
+        assert_eq!(error.to_string(), "Axe was refused: carrying 15.5 kg would exceed the limit of 15 kg");
+        inventory.add(Box::new(Bow::default())).unwrap();
+        assert!((inventory.weight() - 13.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn equips_items_in_their_slot() {
+        let mut inventory = Inventory::new(4, 50.0);
+        inventory.add(Box::new(Axe::default())).unwrap();
+        inventory.add(Box::new(Bow::default())).unwrap();
+        inventory.add(Box::new(Axe::default())).unwrap();
+
+        assert_eq!(
+            inventory.equip(0, Usage::RangedWeapon),
+            Err(InventoryError::IncompatibleSlot { slot: Usage::RangedWeapon, usage: Usage::MeleeWeapon })
+        );
+        inventory.equip(0, Usage::MeleeWeapon).unwrap();
+        assert_eq!(inventory.equip(1, Usage::MeleeWeapon), Err(InventoryError::SlotOccupied(Usage::MeleeWeapon)));
+        assert_eq!(inventory.equip(5, Usage::MeleeWeapon), Err(InventoryError::NoSuchStack(5)));
+
+        assert_eq!(inventory.equipped(Usage::MeleeWeapon).unwrap().attributes().name, "Axe");
+        assert!(inventory.equipped(Usage::Protection).is_none());
+        assert_eq!(inventory.stacks().len(), 2);
+    }
+
+    #[test]
+    fn unequips_only_when_there_is_room() {
+        let mut inventory = Inventory::new(1, 50.0);
+        inventory.add(Box::new(Bow::default())).unwrap();
+        inventory.equip(0, Usage::RangedWeapon).unwrap();
+        inventory.add(Box::new(Axe::default())).unwrap();
+
+        assert_eq!(inventory.unequip(Usage::RangedWeapon), Err(InventoryError::Full { capacity: 1 }));
+        assert!(inventory.equipped(Usage::RangedWeapon).is_some());
+        assert_eq!(inventory.unequip(Usage::Protection), Err(InventoryError::EmptySlot(Usage::Protection)));
+
+        inventory.remove(0).unwrap();
+        inventory.unequip(Usage::RangedWeapon).unwrap();
+        assert!(inventory.equipped(Usage::RangedWeapon).is_none());
+        assert_eq!(inventory.stacks()[0].item().attributes().name, "Bow");
+    }
+
+    #[test]
+    fn filters_by_usage() {
+        let mut inventory = Inventory::new(10, 50.0);
+        inventory.add(Box::new(Bow::default())).unwrap();
+        inventory.add(Box::new(Armor::default())).unwrap();
+        inventory.add(Box::new(Arrow::new())).unwrap();
+        inventory.add(Box::new(Axe::default())).unwrap();
+
+        let ranged: Vec<usize> = inventory.with_usage(Usage::RangedWeapon).map(|(index, _)| index).collect();
+
+        assert_eq!(ranged, vec![0, 2]);
+        assert_eq!(inventory.with_usage(Usage::Protection).count(), 1);
+        assert_eq!(inventory.remove(7).err(), Some(InventoryError::NoSuchStack(7)));
+    }
+}
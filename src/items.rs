@@ -0,0 +1,150 @@
+// The equipment model shared by the factories and the inventory.
+
+use std::any::Any;
+
+/// How an item is equipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Usage {
+    MeleeWeapon,
+    RangedWeapon,
+    Protection
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemAttributes {
+    pub name: String,
+    /// In kilograms.
+    pub weight: f32,
+    pub durability: u32,
+    pub damage: u32,
+    pub defense: u32,
+    pub rarity: Rarity,
+}
+
+pub trait EquipableItem: Any {
+    fn get_usage(&self) -> Usage;
+
+    fn attributes(&self) -> &ItemAttributes;
+
+    /// How many of this item fit in one inventory slot. Equipment doesn't stack.
+    fn max_stack(&self) -> u32 {
+        1
+    }
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// The kinds of items factories know how to create.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum Item {
+    Bow,
+    Axe,
+    Armor
+}
+
+impl Item {
+    pub fn usage(&self) -> Usage {
+        match self {
+            Item::Bow => Usage::RangedWeapon,
+            Item::Axe => Usage::MeleeWeapon,
+            Item::Armor => Usage::Protection,
+        }
+    }
+}
+
+macro_rules! equipable_item {
+    ($item:ident, $usage:expr, $name:expr, $weight:expr, $durability:expr, $damage:expr, $defense:expr) => {
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $item {
+            attributes: ItemAttributes,
+        }
+
+        impl $item {
+            pub fn new(attributes: ItemAttributes) -> Self {
+                Self { attributes }
+            }
+        }
+
+        /// A common item with the standard stats of its kind.
+        impl Default for $item {
+            fn default() -> Self {
+                Self::new(ItemAttributes {
+                    name: String::from($name),
+                    weight: $weight,
+                    durability: $durability,
+                    damage: $damage,
+                    defense: $defense,
+                    rarity: Rarity::Common,
+                })
+            }
+        }
+
+        impl EquipableItem for $item {
+            fn get_usage(&self) -> Usage {
+                $usage
+            }
+
+            fn attributes(&self) -> &ItemAttributes {
+                &self.attributes
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+        }
+    };
+}
+
+equipable_item!(Axe, Usage::MeleeWeapon, "Axe", 3.5, 120, 14, 0);
+equipable_item!(Bow, Usage::RangedWeapon, "Bow", 1.2, 80, 9, 0);
+equipable_item!(Armor, Usage::Protection, "Armor", 12.0, 200, 0, 18);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_have_standard_stats_by_default() {
+        let axe = Axe::default();
+
+        assert_eq!(axe.attributes().name, "Axe");
+        assert_eq!(axe.attributes().rarity, Rarity::Common);
+        assert!(axe.attributes().damage > Bow::default().attributes().damage);
+        assert!(Armor::default().attributes().defense > 0);
+    }
+
+    #[test]
+    fn items_carry_custom_attributes() {
+        let bow: Box<dyn EquipableItem> = Box::new(Bow::new(ItemAttributes {
+            name: String::from("Elven Bow"),
+            weight: 0.8,
+            durability: 150,
+            damage: 15,
+            defense: 0,
+            rarity: Rarity::Epic,
+        }));
+
+        assert_eq!(bow.get_usage(), Usage::RangedWeapon);
+        assert_eq!(bow.attributes().name, "Elven Bow");
+        assert!(bow.attributes().rarity > Rarity::Rare);
+        assert!(bow.as_any().downcast_ref::<Bow>().is_some());
+    }
+
+    #[test]
+    fn item_kinds_know_their_usage() {
+        assert_eq!(Item::Axe.usage(), Axe::default().get_usage());
+        assert_eq!(Item::Bow.usage(), Bow::default().get_usage());
+        assert_eq!(Item::Armor.usage(), Armor::default().get_usage());
+    }
+}
@@ -1,11 +1,29 @@
+// Lets code generated by `design-patterns-derive` name this crate from inside it too.
+extern crate self as design_patterns_rs;
+
+pub mod inventory;
+pub mod items;
+
 // Creational Design Patterns
 pub mod builder;
 pub mod builder_non_consuming;
+pub mod builder_typestate;
+pub mod validation;
 pub mod factory;
+pub mod factory_combinators;
+pub mod factory_data_driven;
 pub mod factory_no_dyn_dispatch;
+pub mod factory_registry;
+pub mod factory_stateful;
 
 // Strutuctural Design Patterns
 pub mod adapter;
 
 // Behavior Design Patterns
-pub mod strategy;
\ No newline at end of file
+pub mod strategy;
+pub mod strategy_adaptive;
+pub mod strategy_dyn_dispatch;
+pub mod strategy_external;
+pub mod strategy_instrumented;
+pub mod strategy_non_comparison;
+pub mod strategy_parallel;
\ No newline at end of file
@@ -1,51 +1,859 @@
 // Source implementations for bubblesort: https://chercher.tech/rust/bubble-sort-rust
-// TODO: Do mergesort, quicksort and others as well!
+//
+// Complexities below are in comparisons for a list of `n` elements. Stable
+// strategies keep equal elements in their original relative order.
 
-#[cfg(test)]
-mod tests {
-    trait SortingStrategy {
-        fn sort<T: PartialOrd>(list: &mut Vec<T>);
-    }
-
-    struct BubbleSorter;
-
-    impl SortingStrategy for BubbleSorter {
-        fn sort<T: PartialOrd>(list: &mut Vec<T>) {
-            let mut swapped = true;
-            while swapped {
-                // No swap means array is sorted.
-                swapped = false;
-                for i in 1..list.len() {
-                    if list[i - 1] > list[i] {
-                        list.swap(i - 1, i);
-                        swapped = true
-                    }
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+pub trait SortingStrategy {
+    /// Identifies the strategy in configuration, see `strategy_dyn_dispatch`.
+    const NAME: &'static str;
+    /// Whether equal elements keep their relative order.
+    const IS_STABLE: bool;
+    /// Whether the strategy sorts in the reverse order of its comparator, like
+    /// `Reversed`. Incomparable values still go last then.
+    const REVERSES: bool = false;
+
+    /// Sorts like `sort_by`, telling `observer` about the swaps and passes it makes.
+    fn sort_by_observed<T, F, O>(list: &mut [T], compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized;
+
+    /// Sorts with `compare`, which must describe a total order.
+    fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], compare: F) {
+        Self::sort_by_observed(list, compare, &mut ());
+    }
+
+    /// Sorts in ascending order, with incomparable values such as NaN ordered
+    /// last. See `sort_partial` to handle them otherwise.
+    fn sort<T: PartialOrd>(list: &mut [T]) {
+        Self::sort_by(list, incomparable_last_after::<T>(Self::REVERSES));
+    }
+
+    fn sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(list: &mut [T], mut key: F) {
+        Self::sort_by(list, |a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Sorts in descending order, with incomparable values such as NaN still ordered last.
+    fn sort_descending<T: PartialOrd>(list: &mut [T])
+    where
+        Self: Sized,
+    {
+        Reversed::<Self>::sort(list);
+    }
+
+    /// Sorts `PartialOrd` values, handling values that can't be compared as `policy` says.
+    ///
+    /// Values that can't even be compared with themselves, like NaN, are found
+    /// before sorting. Any other pair of incomparable values is treated as equal,
+    /// and reported afterwards under `IncomparablePolicy::Error`.
+    fn sort_partial<T: PartialOrd>(list: &mut [T], policy: IncomparablePolicy) -> Result<(), IncomparableError> {
+        let comparable = match policy {
+            IncomparablePolicy::Error => match list.iter().position(is_incomparable) {
+                Some(index) => return Err(IncomparableError { index: Some(index) }),
+                None => list.len(),
+            },
+            IncomparablePolicy::Last => {
+                move_incomparable_last(list);
+                list.iter().position(is_incomparable).unwrap_or(list.len())
+            }
+            IncomparablePolicy::Panic => list.len(),
+        };
+
+        let mut found_incomparable = false;
+        Self::sort_by(&mut list[..comparable], |a, b| match a.partial_cmp(b) {
+            Some(ordering) => ordering,
+            None if policy == IncomparablePolicy::Panic => panic!("sorted values that cannot be compared"),
+            None => {
+                found_incomparable = true;
+                Ordering::Equal
+            }
+        });
+
+        if found_incomparable && policy == IncomparablePolicy::Error {
+            return Err(IncomparableError { index: None });
+        }
+        Ok(())
+    }
+
+    /// Sorts only the elements in `range`, leaving the rest of `list` untouched.
+    fn sort_range<T: PartialOrd, R: RangeBounds<usize>>(list: &mut [T], range: R) {
+        let bounds: (Bound<usize>, Bound<usize>) = (range.start_bound().cloned(), range.end_bound().cloned());
+        Self::sort(&mut list[bounds]);
+    }
+
+    /// Reorders `list` so that the element at `n` is the one that would be there
+    /// once sorted, with no greater element before it and no smaller one after it.
+    ///
+    /// Sorts the whole list unless the strategy knows a faster selection.
+    ///
+    /// # Panics
+    ///
+    /// When `n` is out of bounds.
+    fn select_nth_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], n: usize, compare: F) -> &mut T {
+        assert!(n < list.len(), "cannot select element {} of a list of length {}", n, list.len());
+        Self::sort_by(list, compare);
+        &mut list[n]
+    }
+
+    /// Like `select_nth_by`, with incomparable values such as NaN ordered last.
+    fn select_nth<T: PartialOrd>(list: &mut [T], n: usize) -> &mut T {
+        Self::select_nth_by(list, n, incomparable_last_after::<T>(Self::REVERSES))
+    }
+
+    /// Moves the `k` smallest elements to the front of `list` in sorted order.
+    /// The order of the remaining elements is unspecified.
+    fn partial_sort_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], k: usize, mut compare: F) {
+        if k < list.len() {
+            Self::select_nth_by(list, k, &mut compare);
+        }
+        let k = k.min(list.len());
+        Self::sort_by(&mut list[..k], compare);
+    }
+
+    /// Like `partial_sort_by`, with incomparable values such as NaN ordered last.
+    fn partial_sort<T: PartialOrd>(list: &mut [T], k: usize) {
+        Self::partial_sort_by(list, k, incomparable_last_after::<T>(Self::REVERSES));
+    }
+}
+
+pub fn sort_with_strategy<S: SortingStrategy, T: PartialOrd>(list: &mut [T]) {
+    S::sort(list);
+}
+
+/// Told about the work a strategy does, see `strategy_instrumented`.
+///
+/// Strategies only ever move elements by swapping two of them, so swaps count
+/// every move. A pass is one sweep over the list or a part of it: a round of
+/// bubble sort, a gap of shell sort, a partition, a merge, or a run of
+/// insertion sort.
+pub trait SortObserver {
+    /// Two elements were swapped.
+    fn swapped(&mut self) {}
+
+    /// A pass over the list finished.
+    fn pass_finished(&mut self) {}
+}
+
+impl SortObserver for () {}
+
+/// What `SortingStrategy::sort_partial` does with values that can't be compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IncomparablePolicy {
+    /// Fail without sorting when a value can't be compared with itself, and
+    /// report any other incomparable pair once sorted.
+    Error,
+    /// Move values that can't be compared with themselves to the end, keeping
+    /// their relative order.
+    Last,
+    /// Panic as soon as two values can't be compared.
+    Panic,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IncomparableError {
+    /// Position of a value that can't be compared with itself, if that was the
+    /// problem. The list is left untouched in that case.
+    pub index: Option<usize>,
+}
+
+impl fmt::Display for IncomparableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "value at index {} cannot be compared", index),
+            None => write!(f, "list contains values that cannot be compared with each other"),
+        }
+    }
+}
+
+impl std::error::Error for IncomparableError {}
+
+fn is_incomparable<T: PartialOrd>(value: &T) -> bool {
+    value.partial_cmp(value).is_none()
+}
+
+/// Orders values that can't be compared with themselves after every other value.
+pub(crate) fn incomparable_last<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    match a.partial_cmp(b) {
+        Some(ordering) => ordering,
+        None => is_incomparable(a).cmp(&is_incomparable(b)),
+    }
+}
+
+fn incomparable_first<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    match a.partial_cmp(b) {
+        Some(ordering) => ordering,
+        None => is_incomparable(b).cmp(&is_incomparable(a)),
+    }
+}
+
+/// The comparator ordering incomparable values last once sorted by a strategy
+/// that `reverses` its comparator or not.
+pub(crate) fn incomparable_last_after<T: PartialOrd>(reverses: bool) -> fn(&T, &T) -> Ordering {
+    if reverses {
+        incomparable_first
+    } else {
+        incomparable_last
+    }
+}
+
+/// Stable partition of values that can't be compared with themselves to the end.
+fn move_incomparable_last<T: PartialOrd>(list: &mut [T]) {
+    let mut indices: Vec<usize> = (0..list.len()).filter(|&index| !is_incomparable(&list[index])).collect();
+    indices.extend((0..list.len()).filter(|&index| is_incomparable(&list[index])));
+    apply_permutation(list, &mut indices, &mut ());
+}
+
+/// Sorts in reverse order with the wrapped strategy.
+pub struct Reversed<S>(PhantomData<S>);
+
+const MAX_NAME_LEN: usize = 64;
+
+/// `prefix` followed by `name`, padded with zeros, for names built at compile time.
+/// `trim_padding` turns it back into a name.
+pub(crate) const fn prefixed_name(prefix: &str, name: &str) -> [u8; MAX_NAME_LEN] {
+    let (prefix, name) = (prefix.as_bytes(), name.as_bytes());
+    assert!(prefix.len() + name.len() <= MAX_NAME_LEN, "strategy name is too long");
+
+    let mut bytes = [0; MAX_NAME_LEN];
+    let mut i = 0;
+    while i < prefix.len() + name.len() {
+        bytes[i] = if i < prefix.len() { prefix[i] } else { name[i - prefix.len()] };
+        i += 1;
+    }
+    bytes
+}
+
+pub(crate) const fn trim_padding(mut bytes: &[u8]) -> &str {
+    while let [rest @ .., 0] = bytes {
+        bytes = rest;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(name) => name,
+        Err(_) => panic!("strategy names are UTF-8"),
+    }
+}
+
+impl<S: SortingStrategy> SortingStrategy for Reversed<S> {
+    /// `reversed_` followed by the wrapped strategy's name, e.g. `reversed_merge`.
+    const NAME: &'static str = trim_padding(&prefixed_name("reversed_", S::NAME));
+    const IS_STABLE: bool = S::IS_STABLE;
+    const REVERSES: bool = !S::REVERSES;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        S::sort_by_observed(list, |a, b| compare(b, a), observer);
+    }
+
+    fn select_nth_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], n: usize, mut compare: F) -> &mut T {
+        S::select_nth_by(list, n, |a, b| compare(b, a))
+    }
+}
+
+/// Stable. O(n²) worst and average case, O(n) on sorted input, O(1) extra space.
+pub struct BubbleSorter;
+
+impl SortingStrategy for BubbleSorter {
+    const NAME: &'static str = "bubble";
+    const IS_STABLE: bool = true;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        let mut swapped = true;
+        while swapped {
+            // No swap means array is sorted.
+            swapped = false;
+            for i in 1..list.len() {
+                if compare(&list[i - 1], &list[i]) == Ordering::Greater {
+                    list.swap(i - 1, i);
+                    observer.swapped();
+                    swapped = true
                 }
             }
+            observer.pass_finished();
+        }
+    }
+}
+
+/// Stable. O(n²) worst and average case, O(n) on sorted input, O(1) extra space.
+///
+/// Each pass stops where the previous one made its last swap, since everything
+/// after it is already in place.
+pub struct OptimizedBubbleSorter;
+
+impl SortingStrategy for OptimizedBubbleSorter {
+    const NAME: &'static str = "optimized_bubble";
+    const IS_STABLE: bool = true;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        let mut new_len: usize;
+        let mut len = list.len();
+        loop {
+            new_len = 0;
+            for i in 1..len {
+                if compare(&list[i - 1], &list[i]) == Ordering::Greater {
+                    list.swap(i - 1, i);
+                    observer.swapped();
+                    new_len = i;
+                }
+            }
+            observer.pass_finished();
+            if new_len == 0 {
+                break;
+            }
+            len = new_len;
+        }
+    }
+}
+
+/// Stable. O(n²) worst and average case, O(n) on sorted input, O(1) extra space.
+pub struct InsertionSorter;
+
+impl SortingStrategy for InsertionSorter {
+    const NAME: &'static str = "insertion";
+    const IS_STABLE: bool = true;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        insertion_sort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, observer);
+    }
+}
+
+/// Stable. O(n log n) in every case, O(n) extra space.
+pub struct MergeSorter;
+
+impl SortingStrategy for MergeSorter {
+    const NAME: &'static str = "merge";
+    const IS_STABLE: bool = true;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        merge_sort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, observer);
+    }
+}
+
+/// Unstable. O(n log n) average case, O(n²) worst case, O(log n) extra space.
+///
+/// Pivots are the median of the first, middle and last elements, which keeps
+/// sorted and reverse-sorted input on the fast path.
+pub struct QuickSorter;
+
+impl SortingStrategy for QuickSorter {
+    const NAME: &'static str = "quick";
+    const IS_STABLE: bool = false;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        quicksort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, None, observer);
+    }
+
+    fn select_nth_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], n: usize, mut compare: F) -> &mut T {
+        assert!(n < list.len(), "cannot select element {} of a list of length {}", n, list.len());
+        quickselect(list, n, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, None);
+        &mut list[n]
+    }
+}
+
+/// Unstable. O(n log n) in every case, O(1) extra space.
+pub struct HeapSorter;
+
+impl SortingStrategy for HeapSorter {
+    const NAME: &'static str = "heap";
+    const IS_STABLE: bool = false;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        heapsort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, observer);
+    }
+}
+
+/// Unstable. No proven bound for its gap sequence: around O(n^1.3) in practice
+/// and O(n²) at worst, O(n log n) on sorted input, O(1) extra space.
+///
+/// Uses Ciura's gap sequence, extended by a factor of 2.25 for long lists.
+pub struct ShellSorter;
+
+impl SortingStrategy for ShellSorter {
+    const NAME: &'static str = "shell";
+    const IS_STABLE: bool = false;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        shell_sort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, observer);
+    }
+}
+
+/// Unstable. O(n log n) in every case, O(log n) extra space.
+///
+/// Quicksort that falls back to heapsort once recursion gets deeper than
+/// 2·log₂(n), and to insertion sort for short slices.
+pub struct IntroSorter;
+
+impl SortingStrategy for IntroSorter {
+    const NAME: &'static str = "intro";
+    const IS_STABLE: bool = false;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        introsort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, observer);
+    }
+
+    fn select_nth_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], n: usize, mut compare: F) -> &mut T {
+        assert!(n < list.len(), "cannot select element {} of a list of length {}", n, list.len());
+        quickselect(list, n, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, Some(depth_limit(list.len())));
+        &mut list[n]
+    }
+}
+
+/// Stable. O(n log n) worst case, O(n) on input made of a few ascending or
+/// descending runs, O(n) extra space.
+///
+/// A TimSort-style adaptive merge sort: natural runs are detected, short ones
+/// are extended with insertion sort, and runs are merged while keeping the
+/// run stack balanced.
+pub struct TimSorter;
+
+impl SortingStrategy for TimSorter {
+    const NAME: &'static str = "tim";
+    const IS_STABLE: bool = true;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        tim_sort(list, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less, observer);
+    }
+}
+
+// Slices shorter than this are finished with insertion sort.
+const INSERTION_THRESHOLD: usize = 16;
+
+fn insertion_sort<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool + ?Sized,
+    O: SortObserver + ?Sized,
+{
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && is_less(&v[j], &v[j - 1]) {
+            v.swap(j, j - 1);
+            observer.swapped();
+            j -= 1;
+        }
+    }
+    observer.pass_finished();
+}
+
+// The merge-based sorts order a list of indices instead of the elements
+// themselves, so elements never need to be cloned, then move every element to
+// its final place in one pass. Only that pass swaps elements, while merges of
+// indices still count as passes.
+fn sort_by_indices<T, F, O, S>(v: &mut [T], is_less: &mut F, observer: &mut O, sort_indices: S)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+    S: FnOnce(&mut [usize], &mut dyn FnMut(&usize, &usize) -> bool, &mut O),
+{
+    let mut indices: Vec<usize> = (0..v.len()).collect();
+    {
+        let elements = &*v;
+        sort_indices(&mut indices, &mut |&a: &usize, &b: &usize| is_less(&elements[a], &elements[b]), observer);
+    }
+    apply_permutation(v, &mut indices, observer);
+}
+
+/// Reorders `v` so that `v[i]` becomes the element previously at `indices[i]`.
+/// `indices` is left as the identity permutation.
+pub(crate) fn apply_permutation<T, O: SortObserver + ?Sized>(v: &mut [T], indices: &mut [usize], observer: &mut O) {
+    for start in 0..v.len() {
+        let mut current = start;
+        loop {
+            let source = indices[current];
+            indices[current] = current;
+            if source == start || source == current {
+                break;
+            }
+            v.swap(current, source);
+            observer.swapped();
+            current = source;
+        }
+    }
+}
+
+fn merge_sort<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    sort_by_indices(v, is_less, observer, |indices, is_less, observer| {
+        let mut buffer = indices.to_vec();
+        merge_sort_copies(indices, &mut buffer, is_less, observer);
+    });
+}
+
+fn merge_sort_copies<T: Copy, O: SortObserver + ?Sized>(
+    v: &mut [T],
+    buffer: &mut [T],
+    is_less: &mut dyn FnMut(&T, &T) -> bool,
+    observer: &mut O,
+) {
+    let len = v.len();
+    if len <= 1 {
+        return;
+    }
+
+    let mid = len / 2;
+    merge_sort_copies(&mut v[..mid], &mut buffer[..mid], is_less, observer);
+    merge_sort_copies(&mut v[mid..], &mut buffer[mid..], is_less, observer);
+    merge(v, mid, buffer, is_less);
+    observer.pass_finished();
+}
+
+/// Merges the sorted halves `v[..mid]` and `v[mid..]`, taking from the left on ties.
+pub(crate) fn merge<T: Copy>(v: &mut [T], mid: usize, buffer: &mut [T], is_less: &mut dyn FnMut(&T, &T) -> bool) {
+    let len = v.len();
+    let buffer = &mut buffer[..len];
+    buffer.copy_from_slice(v);
+
+    let (mut left, mut right) = (0, mid);
+    for slot in v.iter_mut() {
+        let take_right = left == mid || (right < len && is_less(&buffer[right], &buffer[left]));
+        if take_right {
+            *slot = buffer[right];
+            right += 1;
+        } else {
+            *slot = buffer[left];
+            left += 1;
+        }
+    }
+}
+
+pub(crate) fn quicksort<T, F, O>(mut v: &mut [T], is_less: &mut F, mut depth_limit: Option<u32>, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    loop {
+        if v.len() <= INSERTION_THRESHOLD {
+            insertion_sort(v, is_less, observer);
+            return;
+        }
+
+        if let Some(limit) = depth_limit.as_mut() {
+            if *limit == 0 {
+                heapsort(v, is_less, observer);
+                return;
+            }
+            *limit -= 1;
+        }
+
+        let pivot = partition(v, is_less, observer);
+        let (left, right) = v.split_at_mut(pivot);
+        let right = &mut right[1..];
+
+        // Recurse into the shorter side so the stack stays O(log n) deep.
+        if left.len() < right.len() {
+            quicksort(left, is_less, depth_limit, observer);
+            v = right;
+        } else {
+            quicksort(right, is_less, depth_limit, observer);
+            v = left;
+        }
+    }
+}
+
+/// Quicksort that only descends into the side holding index `n`.
+fn quickselect<T, F: FnMut(&T, &T) -> bool>(mut v: &mut [T], mut n: usize, is_less: &mut F, mut depth_limit: Option<u32>) {
+    loop {
+        if v.len() <= INSERTION_THRESHOLD {
+            insertion_sort(v, is_less, &mut ());
+            return;
+        }
+
+        if let Some(limit) = depth_limit.as_mut() {
+            if *limit == 0 {
+                heapsort(v, is_less, &mut ());
+                return;
+            }
+            *limit -= 1;
+        }
+
+        let pivot = partition(v, is_less, &mut ());
+        if n == pivot {
+            return;
+        } else if n < pivot {
+            v = &mut v[..pivot];
+        } else {
+            v = &mut v[pivot + 1..];
+            n -= pivot + 1;
+        }
+    }
+}
+
+/// Sorts the first, middle and last elements in place and returns the middle index.
+fn median_of_three<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    let (a, b, c) = (0, v.len() / 2, v.len() - 1);
+    for (x, y) in [(a, b), (b, c), (a, b)] {
+        if is_less(&v[y], &v[x]) {
+            v.swap(x, y);
+            observer.swapped();
+        }
+    }
+    b
+}
+
+/// Hoare-style partition around a median-of-three pivot. Elements equal to the
+/// pivot end up on both sides, so runs of duplicates still split evenly.
+/// Returns the pivot's final index.
+pub(crate) fn partition<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    let pivot = median_of_three(v, is_less, observer);
+    v.swap(0, pivot);
+    observer.swapped();
+
+    let (pivot, rest) = v.split_at_mut(1);
+    let pivot = &pivot[0];
+    let (mut left, mut right) = (0, rest.len());
+    loop {
+        while left < right && is_less(&rest[left], pivot) {
+            left += 1;
+        }
+        while left < right && is_less(pivot, &rest[right - 1]) {
+            right -= 1;
+        }
+        if left >= right {
+            break;
+        }
+        right -= 1;
+        rest.swap(left, right);
+        observer.swapped();
+        left += 1;
+    }
+
+    v.swap(0, left);
+    observer.swapped();
+    observer.pass_finished();
+    left
+}
+
+fn heapsort<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    let len = v.len();
+    for node in (0..len / 2).rev() {
+        sift_down(v, node, len, is_less, observer);
+    }
+    observer.pass_finished();
+    for end in (1..len).rev() {
+        v.swap(0, end);
+        observer.swapped();
+        sift_down(v, 0, end, is_less, observer);
+    }
+    observer.pass_finished();
+}
+
+fn sift_down<T, F, O>(v: &mut [T], mut node: usize, end: usize, is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    loop {
+        let mut child = 2 * node + 1;
+        if child >= end {
+            return;
+        }
+        if child + 1 < end && is_less(&v[child], &v[child + 1]) {
+            child += 1;
+        }
+        if !is_less(&v[node], &v[child]) {
+            return;
+        }
+        v.swap(node, child);
+        observer.swapped();
+        node = child;
+    }
+}
+
+fn shell_sort<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    let len = v.len();
+    let mut gaps = vec![1, 4, 10, 23, 57, 132, 301, 701];
+    while let Some(&largest) = gaps.last() {
+        let next = largest * 9 / 4;
+        if next >= len {
+            break;
+        }
+        gaps.push(next);
+    }
+
+    for &gap in gaps.iter().rev().filter(|&&gap| gap < len) {
+        for i in gap..len {
+            let mut j = i;
+            while j >= gap && is_less(&v[j], &v[j - gap]) {
+                v.swap(j, j - gap);
+                observer.swapped();
+                j -= gap;
+            }
         }
+        observer.pass_finished();
     }
+}
+
+fn introsort<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    quicksort(v, is_less, Some(depth_limit(v.len())), observer);
+}
+
+/// 2·log₂(len), how deep introsort recurses before switching to heapsort.
+fn depth_limit(len: usize) -> u32 {
+    2 * (usize::BITS - len.leading_zeros())
+}
 
-    struct OptimizedBubbleSorter;
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
 
-    impl SortingStrategy for OptimizedBubbleSorter {
-        fn sort<T: PartialOrd>(list: &mut Vec<T>) {
-            let mut new_len: usize;
-            let mut len = list.len();
-            loop {
-                new_len = 0;
-                for i in 1..len {
-                    if list[i - 1] > list[i] {
-                        list.swap(i - 1, i);
-                        new_len = i;
-                    }
+fn min_run_length(mut len: usize) -> usize {
+    let mut remainder = 0;
+    while len >= 64 {
+        remainder |= len & 1;
+        len >>= 1;
+    }
+    len + remainder
+}
+
+fn tim_sort<T, F, O>(v: &mut [T], is_less: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> bool,
+    O: SortObserver + ?Sized,
+{
+    sort_by_indices(v, is_less, observer, |indices, is_less, observer| {
+        let len = indices.len();
+        let min_run = min_run_length(len);
+        let mut buffer = indices.to_vec();
+        let mut runs: Vec<Run> = vec![];
+
+        let mut start = 0;
+        while start < len {
+            let mut end = start + 1;
+            if end < len && is_less(&indices[end], &indices[end - 1]) {
+                // Only strictly descending runs are reversed, which keeps the sort stable.
+                while end < len && is_less(&indices[end], &indices[end - 1]) {
+                    end += 1;
                 }
-                if new_len == 0 {
-                    break;
+                indices[start..end].reverse();
+            } else {
+                while end < len && !is_less(&indices[end], &indices[end - 1]) {
+                    end += 1;
                 }
-                len = new_len;
             }
+
+            let forced_end = len.min(start + min_run);
+            if end < forced_end {
+                end = forced_end;
+                insertion_sort(&mut indices[start..end], is_less, observer);
+            }
+
+            runs.push(Run { start, len: end - start });
+            collapse_runs(indices, &mut buffer, &mut runs, is_less, false, observer);
+            start = end;
         }
+
+        collapse_runs(indices, &mut buffer, &mut runs, is_less, true, observer);
+    });
+}
+
+/// Merges runs on top of the stack until each run is longer than the two above
+/// it combined, or until a single run is left when `all` is set.
+fn collapse_runs<O: SortObserver + ?Sized>(
+    v: &mut [usize],
+    buffer: &mut [usize],
+    runs: &mut Vec<Run>,
+    is_less: &mut dyn FnMut(&usize, &usize) -> bool,
+    all: bool,
+    observer: &mut O,
+) {
+    while runs.len() > 1 {
+        let n = runs.len();
+        let at = if all {
+            n - 2
+        } else if (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len)
+        {
+            if runs[n - 3].len < runs[n - 1].len {
+                n - 3
+            } else {
+                n - 2
+            }
+        } else if runs[n - 2].len <= runs[n - 1].len {
+            n - 2
+        } else {
+            return;
+        };
+
+        let (left, right) = (runs[at], runs[at + 1]);
+        let merged = &mut v[left.start..right.start + right.len];
+        merge(merged, left.len, &mut buffer[left.start..], is_less);
+        observer.pass_finished();
+        runs[at] = Run { start: left.start, len: left.len + right.len };
+        runs.remove(at + 1);
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
 
     #[test]
     fn bubblesort_works() {
@@ -65,10 +873,6 @@ mod tests {
         assert_eq!(list, vec![1, 2, 3, 7]);
     }
 
-    fn sort_with_strategy<T: SortingStrategy, R: PartialOrd>(list: &mut Vec<R>) {
-        T::sort(list);
-    }
-
     #[test]
     fn using_first_bubblesort_strategy() {
         let mut list: Vec<i32> = vec![3, 7, 2, 1];
@@ -86,4 +890,340 @@ mod tests {
 
         assert_eq!(list, vec![1, 2, 3, 7]);
     }
-}
\ No newline at end of file
+
+    // Compares by `key` only, so stability can be checked through `position`.
+    #[derive(Debug, Clone, Copy)]
+    struct Keyed {
+        key: u32,
+        position: usize,
+    }
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.key.partial_cmp(&other.key)
+        }
+    }
+
+    fn pseudo_random(len: usize, modulo: u32) -> Vec<u32> {
+        let mut state: u32 = 2_463_534_242;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state % modulo
+            })
+            .collect()
+    }
+
+    fn inputs() -> Vec<Vec<u32>> {
+        let mut inputs = vec![
+            vec![],
+            vec![1],
+            vec![2, 1],
+            vec![3, 7, 2, 1],
+            (0..100).collect(),
+            (0..100).rev().collect(),
+            vec![5; 50],
+            pseudo_random(1000, 1_000_000),
+            pseudo_random(1000, 4),
+        ];
+        let mut runs: Vec<u32> = (0..300).collect();
+        runs.extend((0..300).rev());
+        runs.extend(0..300);
+        inputs.push(runs);
+        inputs
+    }
+
+    fn assert_sorts<S: SortingStrategy>() {
+        for input in inputs() {
+            let mut expected = input.clone();
+            expected.sort();
+
+            let mut list = input;
+            S::sort(&mut list);
+
+            assert_eq!(list, expected);
+        }
+    }
+
+    fn assert_stable<S: SortingStrategy>() {
+        assert!(S::IS_STABLE);
+
+        for input in inputs() {
+            let mut list: Vec<Keyed> = input
+                .into_iter()
+                .enumerate()
+                .map(|(position, key)| Keyed { key: key % 10, position })
+                .collect();
+            let mut expected = list.clone();
+            expected.sort_by_key(|keyed| keyed.key);
+
+            S::sort(&mut list);
+
+            let positions: Vec<usize> = list.iter().map(|keyed| keyed.position).collect();
+            let expected_positions: Vec<usize> = expected.iter().map(|keyed| keyed.position).collect();
+            assert_eq!(positions, expected_positions);
+        }
+    }
+
+    #[test]
+    fn bubble_sorters_sort_and_are_stable() {
+        assert_sorts::<BubbleSorter>();
+        assert_stable::<BubbleSorter>();
+        assert_sorts::<OptimizedBubbleSorter>();
+        assert_stable::<OptimizedBubbleSorter>();
+    }
+
+    #[test]
+    fn insertion_sort_works_and_is_stable() {
+        assert_sorts::<InsertionSorter>();
+        assert_stable::<InsertionSorter>();
+    }
+
+    #[test]
+    fn merge_sort_works_and_is_stable() {
+        assert_sorts::<MergeSorter>();
+        assert_stable::<MergeSorter>();
+    }
+
+    #[test]
+    fn tim_sort_works_and_is_stable() {
+        assert_sorts::<TimSorter>();
+        assert_stable::<TimSorter>();
+    }
+
+    #[test]
+    fn quicksort_works() {
+        assert_sorts::<QuickSorter>();
+    }
+
+    #[test]
+    fn heapsort_works() {
+        assert_sorts::<HeapSorter>();
+    }
+
+    #[test]
+    fn shell_sort_works() {
+        assert_sorts::<ShellSorter>();
+    }
+
+    #[test]
+    fn introsort_works() {
+        assert_sorts::<IntroSorter>();
+    }
+
+    #[test]
+    fn sorts_values_that_are_not_clone() {
+        let mut list: Vec<String> = vec!["b", "d", "a", "c"].into_iter().map(String::from).collect();
+
+        sort_with_strategy::<MergeSorter, String>(&mut list);
+
+        assert_eq!(list, vec!["a", "b", "c", "d"]);
+    }
+
+    fn assert_orders_floats<S: SortingStrategy>() {
+        let mut list = vec![3.0, f64::NAN, -1.5, 2.0, f64::NAN, 0.0];
+        assert_eq!(S::sort_partial(&mut list, IncomparablePolicy::Last), Ok(()));
+        assert_eq!(&list[..4], &[-1.5, 0.0, 2.0, 3.0]);
+        assert!(list[4].is_nan() && list[5].is_nan());
+
+        let mut list = vec![3.0, f64::NAN, -1.5];
+        assert_eq!(S::sort_partial(&mut list, IncomparablePolicy::Error), Err(IncomparableError { index: Some(1) }));
+        assert!(list[0] == 3.0 && list[1].is_nan() && list[2] == -1.5);
+
+        let mut list = vec![3.0, f64::NAN, 1.0, 2.0];
+        S::sort(&mut list);
+        assert_eq!(&list[..3], &[1.0, 2.0, 3.0]);
+        assert!(list[3].is_nan());
+
+        let mut list = vec![2.5, f64::NAN, -1.0, 0.5];
+        S::sort_descending(&mut list);
+        assert_eq!(&list[..3], &[2.5, 0.5, -1.0]);
+        assert!(list[3].is_nan());
+    }
+
+    #[test]
+    fn every_strategy_moves_nan_to_the_end() {
+        assert_orders_floats::<BubbleSorter>();
+        assert_orders_floats::<OptimizedBubbleSorter>();
+        assert_orders_floats::<InsertionSorter>();
+        assert_orders_floats::<MergeSorter>();
+        assert_orders_floats::<QuickSorter>();
+        assert_orders_floats::<HeapSorter>();
+        assert_orders_floats::<ShellSorter>();
+        assert_orders_floats::<IntroSorter>();
+        assert_orders_floats::<TimSorter>();
+        assert_orders_floats::<Reversed<Reversed<MergeSorter>>>();
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be compared")]
+    fn panics_on_nan_when_asked_to() {
+        let _ = MergeSorter::sort_partial(&mut [1.0, f64::NAN, 0.0], IncomparablePolicy::Panic);
+    }
+
+    #[test]
+    fn reports_values_that_cannot_be_compared_with_each_other() {
+        #[derive(Debug, PartialEq)]
+        struct Version(u32, u32);
+
+        impl PartialOrd for Version {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                if self.0 == other.0 {
+                    self.1.partial_cmp(&other.1)
+                } else {
+                    None
+                }
+            }
+        }
+
+        let mut list = vec![Version(1, 2), Version(2, 0), Version(1, 1)];
+
+        let error = InsertionSorter::sort_partial(&mut list, IncomparablePolicy::Error).err().unwrap();
+
+        assert_eq!(error, IncomparableError { index: None });
+        assert_eq!(error.to_string(), "list contains values that cannot be compared with each other");
+    }
+
+    #[test]
+    fn sorts_with_a_comparator_or_a_key() {
+        let mut words = vec!["pear", "fig", "banana", "kiwi"];
+
+        TimSorter::sort_by(&mut words, |a, b| b.len().cmp(&a.len()));
+        assert_eq!(words, vec!["banana", "pear", "kiwi", "fig"]);
+
+        QuickSorter::sort_by_key(&mut words, |word| word.chars().last());
+        assert_eq!(words, vec!["banana", "fig", "kiwi", "pear"]);
+    }
+
+    #[test]
+    fn reversed_strategies_stay_stable() {
+        let mut list: Vec<Keyed> = (0..20).map(|position| Keyed { key: (position % 3) as u32, position }).collect();
+
+        Reversed::<MergeSorter>::sort(&mut list);
+
+        let keys: Vec<u32> = list.iter().map(|keyed| keyed.key).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(list.windows(2).filter(|pair| pair[0].key == pair[1].key).all(|pair| pair[0].position < pair[1].position));
+        assert_eq!(Reversed::<MergeSorter>::NAME, "reversed_merge");
+    }
+
+    fn assert_selects<S: SortingStrategy>() {
+        for input in inputs().into_iter().filter(|input| !input.is_empty()) {
+            let mut expected = input.clone();
+            expected.sort();
+
+            for &n in &[0, input.len() / 2, input.len() - 1] {
+                let mut list = input.clone();
+                assert_eq!(*S::select_nth(&mut list, n), expected[n]);
+                assert!(list[..n].iter().all(|value| *value <= expected[n]));
+                assert!(list[n..].iter().all(|value| *value >= expected[n]));
+            }
+
+            let k = input.len().min(10);
+            let mut list = input;
+            S::partial_sort(&mut list, k);
+            assert_eq!(&list[..k], &expected[..k]);
+        }
+    }
+
+    #[test]
+    fn every_strategy_selects_and_partially_sorts() {
+        assert_selects::<BubbleSorter>();
+        assert_selects::<OptimizedBubbleSorter>();
+        assert_selects::<InsertionSorter>();
+        assert_selects::<MergeSorter>();
+        assert_selects::<QuickSorter>();
+        assert_selects::<HeapSorter>();
+        assert_selects::<ShellSorter>();
+        assert_selects::<IntroSorter>();
+        assert_selects::<TimSorter>();
+        assert_selects::<Reversed<Reversed<QuickSorter>>>();
+    }
+
+    #[test]
+    fn sorts_arrays_and_ranges() {
+        let mut array = [5, 3, 9, 1, 7, 2];
+
+        ShellSorter::sort_range(&mut array, 1..4);
+        assert_eq!(array, [5, 1, 3, 9, 7, 2]);
+
+        IntroSorter::sort_range(&mut array, 3..);
+        assert_eq!(array, [5, 1, 3, 2, 7, 9]);
+
+        BubbleSorter::sort_range(&mut array, ..=2);
+        assert_eq!(array, [1, 3, 5, 2, 7, 9]);
+    }
+
+    #[test]
+    fn sorts_the_halves_of_a_deque() {
+        let mut deque: VecDeque<u32> = (0..6).collect();
+        deque.rotate_left(4);
+        deque.push_front(8);
+        deque.push_back(7);
+
+        let (front, back) = deque.as_mut_slices();
+        HeapSorter::sort(front);
+        OptimizedBubbleSorter::sort(back);
+
+        let mut halves = deque.iter().copied().collect::<Vec<u32>>();
+        halves.sort();
+        assert_eq!(halves, vec![0, 1, 2, 3, 4, 5, 7, 8]);
+        let (front, back) = deque.as_slices();
+        assert!(front.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(back.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn takes_the_top_k() {
+        let mut scores = [12.5, 99.0, 40.0, 87.5, 3.0];
+
+        Reversed::<QuickSorter>::partial_sort_by(&mut scores, 3, |a: &f64, b: &f64| a.total_cmp(b));
+        assert_eq!(&scores[..3], &[99.0, 87.5, 40.0][..]);
+
+        let mut scores = [12.5, f64::NAN, 99.0, 40.0];
+        MergeSorter::partial_sort(&mut scores, 10);
+        assert_eq!(&scores[..3], &[12.5, 40.0, 99.0]);
+        assert!(scores[3].is_nan());
+    }
+
+    #[test]
+    fn reversed_strategies_select_nan_last() {
+        let mut scores = [1.0, f64::NAN, 3.0, 2.0];
+        Reversed::<QuickSorter>::partial_sort(&mut scores, 1);
+        assert_eq!(scores[0], 3.0);
+
+        let mut scores = [1.0, f64::NAN, 3.0, 2.0];
+        assert_eq!(*Reversed::<IntroSorter>::select_nth(&mut scores, 0), 3.0);
+        assert!(Reversed::<MergeSorter>::select_nth(&mut scores, 3).is_nan());
+
+        let mut scores = [1.0, f64::NAN, 3.0, 2.0];
+        Reversed::<HeapSorter>::partial_sort(&mut scores, 4);
+        assert_eq!(&scores[..3], &[3.0, 2.0, 1.0]);
+        assert!(scores[3].is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot select element 3 of a list of length 3")]
+    fn refuses_to_select_out_of_bounds() {
+        IntroSorter::select_nth(&mut [1, 2, 3], 3);
+    }
+
+    #[test]
+    fn applies_permutations() {
+        let mut list = vec!['a', 'b', 'c', 'd', 'e'];
+        let mut indices = vec![2, 0, 1, 4, 3];
+
+        apply_permutation(&mut list, &mut indices, &mut ());
+
+        assert_eq!(list, vec!['c', 'a', 'b', 'e', 'd']);
+        assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+    }
+}
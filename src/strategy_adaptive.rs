@@ -0,0 +1,312 @@
+// Picks a sorting strategy by looking at the input first. Profiling is cheap next
+// to sorting: runs are counted in one pass, while inversions and duplicates are
+// estimated from a small sample.
+
+use crate::strategy::*;
+use std::cmp::Ordering;
+use std::fmt;
+
+// Lists up to this length count as tiny.
+const TINY_LEN: usize = 32;
+// How many pairs are compared to estimate the share of inversions.
+const INVERSION_SAMPLES: usize = 256;
+// How many elements are sorted to estimate the share of duplicates.
+const DUPLICATE_SAMPLES: usize = 64;
+
+/// What `AdaptiveSorter` measured about a list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    pub len: usize,
+    /// Number of ascending or strictly descending runs, 1 for a sorted or reversed list.
+    pub runs: usize,
+    /// Estimated share of pairs that are out of order, from 0 when sorted to 1 when reversed.
+    pub inversions: f64,
+    /// Estimated share of elements equal to another one.
+    pub duplicates: f64,
+}
+
+/// Why `AdaptiveSorter` chose a strategy. Each reason maps to one strategy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    /// Tiny and nearly sorted: `OptimizedBubbleSorter` stops after a pass or two.
+    TinyAndNearlySorted,
+    /// Tiny: `InsertionSorter` beats everything else on a handful of elements.
+    Tiny,
+    /// Long ascending or descending runs, which `TimSorter` merges directly.
+    FewRuns,
+    /// Mostly ordered in either direction: `MergeSorter` never degrades.
+    PartiallyOrdered,
+    /// Many equal elements, which `QuickSorter` splits evenly around its pivot.
+    ManyDuplicates,
+    /// No order to exploit: `IntroSorter` is the fastest on average.
+    Unordered,
+}
+
+impl Reason {
+    /// The name of the strategy chosen for this reason.
+    pub fn strategy(&self) -> &'static str {
+        match self {
+            Reason::TinyAndNearlySorted => OptimizedBubbleSorter::NAME,
+            Reason::Tiny => InsertionSorter::NAME,
+            Reason::FewRuns => TimSorter::NAME,
+            Reason::PartiallyOrdered => MergeSorter::NAME,
+            Reason::ManyDuplicates => QuickSorter::NAME,
+            Reason::Unordered => IntroSorter::NAME,
+        }
+    }
+}
+
+/// The profile of a list and the strategy chosen for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decision {
+    pub profile: Profile,
+    pub reason: Reason,
+}
+
+impl Decision {
+    pub fn strategy(&self) -> &'static str {
+        self.reason.strategy()
+    }
+
+    fn sort_by_observed<T, F, O>(&self, list: &mut [T], compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        match self.reason {
+            Reason::TinyAndNearlySorted => OptimizedBubbleSorter::sort_by_observed(list, compare, observer),
+            Reason::Tiny => InsertionSorter::sort_by_observed(list, compare, observer),
+            Reason::FewRuns => TimSorter::sort_by_observed(list, compare, observer),
+            Reason::PartiallyOrdered => MergeSorter::sort_by_observed(list, compare, observer),
+            Reason::ManyDuplicates => QuickSorter::sort_by_observed(list, compare, observer),
+            Reason::Unordered => IntroSorter::sort_by_observed(list, compare, observer),
+        }
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let profile = &self.profile;
+        let why = match self.reason {
+            Reason::TinyAndNearlySorted => "tiny and nearly sorted",
+            Reason::Tiny => "tiny",
+            Reason::FewRuns => "made of a few long runs",
+            Reason::PartiallyOrdered => "partially ordered",
+            Reason::ManyDuplicates => "full of duplicates",
+            Reason::Unordered => "unordered",
+        };
+        write!(
+            f,
+            "chose {} because the list is {} ({} elements, {} runs, ~{:.0}% inversions, ~{:.0}% duplicates)",
+            self.strategy(),
+            why,
+            profile.len,
+            profile.runs,
+            profile.inversions * 100.0,
+            profile.duplicates * 100.0
+        )
+    }
+}
+
+/// Unstable. Profiles the list, then sorts it with the strategy that suits it best.
+pub struct AdaptiveSorter;
+
+impl AdaptiveSorter {
+    /// Profiles `list` and decides how to sort it, without sorting it.
+    pub fn analyze<T: PartialOrd>(list: &[T]) -> Decision {
+        Self::analyze_by(list, incomparable_last_after::<T>(Self::REVERSES))
+    }
+
+    pub fn analyze_by<T, F: FnMut(&T, &T) -> Ordering>(list: &[T], compare: F) -> Decision {
+        let profile = profile(list, compare);
+        Decision { profile, reason: decide(&profile) }
+    }
+
+    /// Sorts like `sort`, returning what was decided.
+    pub fn sort_with_report<T: PartialOrd>(list: &mut [T]) -> Decision {
+        // The comparator `sort` uses.
+        Self::sort_by_with_report(list, incomparable_last_after::<T>(Self::REVERSES))
+    }
+
+    pub fn sort_by_with_report<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], mut compare: F) -> Decision {
+        let decision = Self::analyze_by(list, &mut compare);
+        decision.sort_by_observed(list, compare, &mut ());
+        decision
+    }
+}
+
+impl SortingStrategy for AdaptiveSorter {
+    const NAME: &'static str = "adaptive";
+    const IS_STABLE: bool = false;
+
+    fn sort_by_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        Self::analyze_by(list, &mut compare).sort_by_observed(list, compare, observer);
+    }
+}
+
+fn profile<T, F: FnMut(&T, &T) -> Ordering>(list: &[T], mut compare: F) -> Profile {
+    let len = list.len();
+    let mut runs = 0;
+    let mut start = 0;
+    while start < len {
+        let mut end = start + 1;
+        if end < len && compare(&list[end], &list[start]) == Ordering::Less {
+            while end < len && compare(&list[end], &list[end - 1]) == Ordering::Less {
+                end += 1;
+            }
+        } else {
+            while end < len && compare(&list[end], &list[end - 1]) != Ordering::Less {
+                end += 1;
+            }
+        }
+        runs += 1;
+        start = end;
+    }
+
+    let mut inversions = 0.0;
+    if len >= 2 {
+        // Deterministic xorshift, so the same list always gets the same decision.
+        let mut state = len as u64 | 1;
+        let mut inverted = 0;
+        for _ in 0..INVERSION_SAMPLES {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let i = (state % len as u64) as usize;
+            let j = ((state >> 32) % len as u64) as usize;
+            let (i, j) = if i <= j { (i, j) } else { (j, i) };
+            if i != j && compare(&list[j], &list[i]) == Ordering::Less {
+                inverted += 1;
+            }
+        }
+        inversions = inverted as f64 / INVERSION_SAMPLES as f64;
+    }
+
+    let mut duplicates = 0.0;
+    let step = len / DUPLICATE_SAMPLES + 1;
+    let mut sample: Vec<&T> = list.iter().step_by(step).collect();
+    if sample.len() >= 2 {
+        InsertionSorter::sort_by(&mut sample, |a, b| compare(a, b));
+        let equal = sample.windows(2).filter(|pair| compare(pair[0], pair[1]) == Ordering::Equal).count();
+        duplicates = equal as f64 / (sample.len() - 1) as f64;
+    }
+
+    Profile { len, runs, inversions, duplicates }
+}
+
+fn decide(profile: &Profile) -> Reason {
+    let ordered = profile.inversions.min(1.0 - profile.inversions);
+
+    if profile.len <= TINY_LEN {
+        if profile.runs <= 2 {
+            Reason::TinyAndNearlySorted
+        } else {
+            Reason::Tiny
+        }
+    } else if profile.runs <= profile.len / TINY_LEN + 1 || ordered <= 0.05 {
+        Reason::FewRuns
+    } else if ordered <= 0.25 {
+        Reason::PartiallyOrdered
+    } else if profile.duplicates >= 0.5 {
+        Reason::ManyDuplicates
+    } else {
+        Reason::Unordered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random(len: usize, modulo: u32) -> Vec<u32> {
+        let mut state: u32 = 2_463_534_242;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state % modulo
+            })
+            .collect()
+    }
+
+    fn assert_chooses(list: Vec<u32>, reason: Reason) {
+        let mut expected = list.clone();
+        expected.sort();
+        let mut list = list;
+
+        let decision = AdaptiveSorter::sort_with_report(&mut list);
+
+        assert_eq!(decision.reason, reason, "{}", decision);
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    fn uses_bubble_sort_for_tiny_nearly_sorted_lists() {
+        assert_chooses(vec![1, 2, 3, 5, 4, 6], Reason::TinyAndNearlySorted);
+        assert_chooses(vec![9, 1, 7, 3, 5], Reason::Tiny);
+    }
+
+    #[test]
+    fn uses_tim_sort_for_long_runs() {
+        let mut list: Vec<u32> = (0..5000).collect();
+        list.extend((0..5000).rev());
+        assert_chooses(list, Reason::FewRuns);
+        assert_chooses((0..10_000).rev().collect(), Reason::FewRuns);
+    }
+
+    #[test]
+    fn uses_merge_sort_for_partially_ordered_lists() {
+        let list: Vec<u32> = (0..10_000).zip(pseudo_random(10_000, 4000)).map(|(i, noise)| i + noise).collect();
+        assert_chooses(list, Reason::PartiallyOrdered);
+    }
+
+    #[test]
+    fn uses_quicksort_for_many_duplicates() {
+        assert_chooses(pseudo_random(10_000, 8), Reason::ManyDuplicates);
+        assert_chooses(pseudo_random(10_000, 1_000_000), Reason::Unordered);
+    }
+
+    #[test]
+    fn explains_its_decision() {
+        let decision = AdaptiveSorter::analyze(&[3.0, 1.0, f64::NAN, 2.0]);
+
+        assert_eq!(decision.profile.len, 4);
+        assert_eq!(decision.profile.runs, 2);
+        assert_eq!(decision.strategy(), "optimized_bubble");
+        assert!(decision.to_string().starts_with("chose optimized_bubble because the list is tiny and nearly sorted (4 elements, 2 runs"));
+    }
+
+    #[test]
+    fn is_a_sorting_strategy() {
+        let mut list = vec![2.5, f64::NAN, -1.0, 0.5];
+
+        sort_with_strategy::<AdaptiveSorter, f64>(&mut list);
+
+        assert_eq!(&list[..3], &[-1.0, 0.5, 2.5]);
+        assert!(list[3].is_nan());
+    }
+
+    #[test]
+    fn reports_on_the_sort_it_would_make() {
+        let noisy: Vec<f64> = pseudo_random(2_000, 300)
+            .into_iter()
+            .map(|value| if value % 11 == 0 { f64::NAN } else { value as f64 })
+            .collect();
+
+        for input in [vec![3.0, f64::NAN, 1.0], noisy] {
+            let mut sorted = input.clone();
+            AdaptiveSorter::sort(&mut sorted);
+            let mut reported = input;
+            AdaptiveSorter::sort_with_report(&mut reported);
+
+            let bits = |list: &[f64]| list.iter().map(|value| value.to_bits()).collect::<Vec<u64>>();
+            assert_eq!(bits(&reported), bits(&sorted));
+            assert!(sorted.last().unwrap().is_nan());
+        }
+    }
+}
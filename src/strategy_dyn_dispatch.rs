@@ -0,0 +1,171 @@
+// `SortingStrategy::sort` is generic over the element type, so it can't be called
+// through a trait object. `DynSortingStrategy<T>` fixes the element type instead,
+// which lets strategies be boxed, picked by name from configuration and swapped
+// while the program runs.
+
+use crate::strategy::*;
+use crate::strategy_adaptive::AdaptiveSorter;
+use std::fmt;
+
+pub trait DynSortingStrategy<T> {
+    fn name(&self) -> &'static str;
+
+    fn is_stable(&self) -> bool;
+
+    fn sort(&self, list: &mut [T]);
+}
+
+impl<S: SortingStrategy, T: PartialOrd> DynSortingStrategy<T> for S {
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn is_stable(&self) -> bool {
+        S::IS_STABLE
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        S::sort(list);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnknownStrategy(pub String);
+
+impl fmt::Display for UnknownStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown sorting strategy `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownStrategy {}
+
+/// Names of every strategy `strategy_by_name` knows about.
+pub const STRATEGY_NAMES: &[&str] = &[
+    BubbleSorter::NAME,
+    OptimizedBubbleSorter::NAME,
+    InsertionSorter::NAME,
+    MergeSorter::NAME,
+    QuickSorter::NAME,
+    HeapSorter::NAME,
+    ShellSorter::NAME,
+    IntroSorter::NAME,
+    TimSorter::NAME,
+    AdaptiveSorter::NAME,
+];
+
+pub fn strategy_by_name<T: PartialOrd + 'static>(name: &str) -> Result<Box<dyn DynSortingStrategy<T>>, UnknownStrategy> {
+    let strategy: Box<dyn DynSortingStrategy<T>> = match name {
+        BubbleSorter::NAME => Box::new(BubbleSorter),
+        OptimizedBubbleSorter::NAME => Box::new(OptimizedBubbleSorter),
+        InsertionSorter::NAME => Box::new(InsertionSorter),
+        MergeSorter::NAME => Box::new(MergeSorter),
+        QuickSorter::NAME => Box::new(QuickSorter),
+        HeapSorter::NAME => Box::new(HeapSorter),
+        ShellSorter::NAME => Box::new(ShellSorter),
+        IntroSorter::NAME => Box::new(IntroSorter),
+        TimSorter::NAME => Box::new(TimSorter),
+        AdaptiveSorter::NAME => Box::new(AdaptiveSorter),
+        _ => return Err(UnknownStrategy(String::from(name))),
+    };
+    Ok(strategy)
+}
+
+/// Sorts with whichever strategy it currently holds.
+pub struct Sorter<T> {
+    strategy: Box<dyn DynSortingStrategy<T>>,
+}
+
+impl<T> Sorter<T> {
+    pub fn new(strategy: Box<dyn DynSortingStrategy<T>>) -> Self {
+        Self { strategy }
+    }
+
+    pub fn strategy(&self) -> &dyn DynSortingStrategy<T> {
+        self.strategy.as_ref()
+    }
+
+    /// Swaps in another strategy, returning the previous one.
+    pub fn set_strategy(&mut self, strategy: Box<dyn DynSortingStrategy<T>>) -> Box<dyn DynSortingStrategy<T>> {
+        std::mem::replace(&mut self.strategy, strategy)
+    }
+
+    pub fn sort(&self, list: &mut [T]) {
+        self.strategy.sort(list);
+    }
+}
+
+impl<T: PartialOrd + 'static> Sorter<T> {
+    pub fn from_name(name: &str) -> Result<Self, UnknownStrategy> {
+        strategy_by_name(name).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_through_a_trait_object() {
+        let strategy: Box<dyn DynSortingStrategy<i32>> = Box::new(OptimizedBubbleSorter);
+        let mut list = vec![3, 7, 2, 1];
+
+        strategy.sort(&mut list);
+
+        assert_eq!(list, vec![1, 2, 3, 7]);
+        assert_eq!(strategy.name(), "optimized_bubble");
+        assert!(strategy.is_stable());
+    }
+
+    #[test]
+    fn looks_up_every_strategy_by_name() {
+        for &name in STRATEGY_NAMES {
+            let strategy = strategy_by_name::<i32>(name).unwrap();
+            let mut list = vec![3, 7, 2, 1];
+
+            strategy.sort(&mut list);
+
+            assert_eq!(strategy.name(), name);
+            assert_eq!(list, vec![1, 2, 3, 7]);
+        }
+    }
+
+    #[test]
+    fn fails_on_unknown_names() {
+        let error = strategy_by_name::<i32>("bogo").err().unwrap();
+
+        assert_eq!(error, UnknownStrategy(String::from("bogo")));
+        assert_eq!(error.to_string(), "unknown sorting strategy `bogo`");
+    }
+
+    #[test]
+    fn swaps_strategies_at_runtime() {
+        let mut sorter = Sorter::from_name("bubble").unwrap();
+        let mut list = vec![3.5, 7.25, 2.0, 1.5];
+
+        sorter.sort(&mut list);
+        let previous = sorter.set_strategy(strategy_by_name("heap").unwrap());
+        list.reverse();
+        sorter.sort(&mut list);
+
+        assert_eq!(previous.name(), "bubble");
+        assert_eq!(sorter.strategy().name(), "heap");
+        assert!(!sorter.strategy().is_stable());
+        assert_eq!(list, vec![1.5, 2.0, 3.5, 7.25]);
+    }
+
+    #[test]
+    fn holds_strategies_chosen_by_configuration() {
+        let config = "merge, quick, tim";
+        let strategies: Vec<Box<dyn DynSortingStrategy<String>>> = config
+            .split(", ")
+            .map(|name| strategy_by_name(name).unwrap())
+            .collect();
+
+        for strategy in &strategies {
+            let mut list: Vec<String> = vec![String::from("b"), String::from("a")];
+            strategy.sort(&mut list);
+            assert_eq!(list, vec!["a", "b"]);
+        }
+    }
+}
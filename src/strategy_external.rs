@@ -0,0 +1,503 @@
+// Sorts streams that don't fit in memory. Records are read until the memory budget
+// is used up, sorted in memory with a `SortingStrategy` and written to a temporary
+// run file. The runs are then merged into the output with a binary heap holding
+// the smallest unread record of each run. At most `max_fan_in` runs are merged at
+// once, so with more runs than that, groups of them are first merged into longer
+// runs, over as many passes as needed.
+//
+// How records are read and written is up to a `RecordCodec`. `LineCodec` handles
+// line-delimited text and `LengthPrefixedCodec` length-prefixed binary records.
+
+use crate::strategy::{MergeSorter, SortingStrategy};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Reads and writes the records being sorted.
+pub trait RecordCodec {
+    type Record;
+
+    fn write<W: Write>(&self, record: &Self::Record, writer: &mut W) -> io::Result<()>;
+
+    /// Reads the next record, or `None` at the end of the input.
+    fn read<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>>;
+
+    /// Roughly how many bytes of memory a record takes, for the memory budget.
+    fn size_of(&self, record: &Self::Record) -> usize;
+}
+
+/// Records are lines of UTF-8 text, without their line ending.
+pub struct LineCodec;
+
+impl RecordCodec for LineCodec {
+    type Record = String;
+
+    fn write<W: Write>(&self, record: &Self::Record, writer: &mut W) -> io::Result<()> {
+        writer.write_all(record.as_bytes())?;
+        writer.write_all(b"\n")
+    }
+
+    fn read<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>> {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+
+    fn size_of(&self, record: &Self::Record) -> usize {
+        mem::size_of::<String>() + record.len()
+    }
+}
+
+/// Records are byte strings, each preceded by its length as a little-endian `u64`.
+pub struct LengthPrefixedCodec;
+
+impl RecordCodec for LengthPrefixedCodec {
+    type Record = Vec<u8>;
+
+    fn write<W: Write>(&self, record: &Self::Record, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&(record.len() as u64).to_le_bytes())?;
+        writer.write_all(record)
+    }
+
+    fn read<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>> {
+        if reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let mut len = [0; 8];
+        reader.read_exact(&mut len)?;
+        let len = u64::from_le_bytes(len);
+        // Grows with the bytes actually read, so a corrupt length can't allocate
+        // more than the input holds.
+        let mut record = Vec::new();
+        reader.by_ref().take(len).read_to_end(&mut record)?;
+        if u64::try_from(record.len()) != Ok(len) {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "record is shorter than its length"));
+        }
+        Ok(Some(record))
+    }
+
+    fn size_of(&self, record: &Self::Record) -> usize {
+        mem::size_of::<Vec<u8>>() + record.len()
+    }
+}
+
+/// What an external sort did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExternalSortReport {
+    pub records: u64,
+    /// Runs written to disk, 0 when every record fit in the memory budget.
+    pub runs: usize,
+    /// Times the records went through a merge, 0 when there were no runs.
+    pub merge_passes: usize,
+}
+
+// The smallest buffer a run is read or written through while merging.
+const MIN_MERGE_BUFFER: usize = 512;
+
+/// Sorts records read with `C` using at most about `memory_budget` bytes of
+/// memory, sorting each run with `S`.
+///
+/// While merging, the budget is split between the buffers of the runs being
+/// read and of the output, which can lower the fan-in below `max_fan_in`.
+pub struct ExternalSorter<C, S = MergeSorter> {
+    codec: C,
+    memory_budget: usize,
+    max_fan_in: usize,
+    temp_dir: PathBuf,
+    strategy: PhantomData<S>,
+}
+
+impl<C: RecordCodec> ExternalSorter<C> {
+    /// Sorts with a 64 MiB memory budget, merging up to 64 runs at once and
+    /// writing runs to the system's temporary directory.
+    pub fn new(codec: C) -> Self {
+        Self {
+            codec,
+            memory_budget: 64 * 1024 * 1024,
+            max_fan_in: 64,
+            temp_dir: std::env::temp_dir(),
+            strategy: PhantomData,
+        }
+    }
+}
+
+impl<C, S> ExternalSorter<C, S>
+where
+    C: RecordCodec,
+    C::Record: Ord,
+    S: SortingStrategy,
+{
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// Merges at most `runs` run files at once, which bounds the open files.
+    ///
+    /// # Panics
+    ///
+    /// When `runs` is less than 2.
+    pub fn with_max_fan_in(mut self, runs: usize) -> Self {
+        assert!(runs >= 2, "cannot merge fewer than 2 runs at once");
+        self.max_fan_in = runs;
+        self
+    }
+
+    /// Writes runs to a fresh directory inside `dir`, removed once sorting ends.
+    pub fn with_temp_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    /// Sorts runs with another in-memory strategy.
+    pub fn with_strategy<S2: SortingStrategy>(self) -> ExternalSorter<C, S2> {
+        ExternalSorter {
+            codec: self.codec,
+            memory_budget: self.memory_budget,
+            max_fan_in: self.max_fan_in,
+            temp_dir: self.temp_dir,
+            strategy: PhantomData,
+        }
+    }
+
+    pub fn sort<R: Read, W: Write>(&self, input: R, output: W) -> io::Result<ExternalSortReport> {
+        let mut input = BufReader::new(input);
+        let mut output = BufWriter::new(output);
+        let mut report = ExternalSortReport { records: 0, runs: 0, merge_passes: 0 };
+        let mut runs: Option<RunFiles> = None;
+        let mut buffer = Vec::new();
+        let mut buffered_bytes = 0;
+
+        while let Some(record) = self.codec.read(&mut input)? {
+            report.records += 1;
+            buffered_bytes += self.codec.size_of(&record);
+            buffer.push(record);
+
+            if buffered_bytes >= self.memory_budget {
+                let runs = match runs.as_mut() {
+                    Some(runs) => runs,
+                    None => runs.insert(RunFiles::create(&self.temp_dir)?),
+                };
+                self.write_run(runs, &mut buffer)?;
+                buffered_bytes = 0;
+            }
+        }
+
+        match runs {
+            None => {
+                S::sort_by(&mut buffer, Ord::cmp);
+                for record in &buffer {
+                    self.codec.write(record, &mut output)?;
+                }
+            }
+            Some(mut runs) => {
+                if !buffer.is_empty() {
+                    self.write_run(&mut runs, &mut buffer)?;
+                }
+                report.runs = runs.count;
+                report.merge_passes = self.merge(&mut runs, &mut output)?;
+            }
+        }
+
+        output.flush()?;
+        Ok(report)
+    }
+
+    fn write_run(&self, runs: &mut RunFiles, buffer: &mut Vec<C::Record>) -> io::Result<()> {
+        S::sort_by(buffer, Ord::cmp);
+        let mut writer = BufWriter::new(File::create(runs.next_path())?);
+        for record in buffer.drain(..) {
+            self.codec.write(&record, &mut writer)?;
+        }
+        writer.flush()
+    }
+
+    /// Merges every run into `output`, returning how many passes it took.
+    fn merge<W: Write>(&self, runs: &mut RunFiles, output: &mut W) -> io::Result<usize> {
+        // Each of the `fan_in` readers and the writer gets an equal share of the budget.
+        let fan_in = self.max_fan_in.min((self.memory_budget / MIN_MERGE_BUFFER).saturating_sub(1)).max(2);
+        let buffer_size = (self.memory_budget / (fan_in + 1)).max(MIN_MERGE_BUFFER);
+
+        // Groups of consecutive runs are merged in order, keeping equal records
+        // in their input order across passes.
+        let mut pending: Vec<usize> = (0..runs.count).collect();
+        let mut passes = 1;
+        while pending.len() > fan_in {
+            let mut merged = Vec::with_capacity(pending.len() / fan_in + 1);
+            for group in pending.chunks(fan_in) {
+                if let [run] = group {
+                    merged.push(*run);
+                    continue;
+                }
+                let run = runs.count;
+                let mut writer = BufWriter::with_capacity(buffer_size, File::create(runs.next_path())?);
+                self.merge_group(runs, group, buffer_size, &mut writer)?;
+                writer.flush()?;
+                for &merged_run in group {
+                    fs::remove_file(runs.path(merged_run))?;
+                }
+                merged.push(run);
+            }
+            pending = merged;
+            passes += 1;
+        }
+
+        self.merge_group(runs, &pending, buffer_size, output)?;
+        Ok(passes)
+    }
+
+    fn merge_group<W: Write>(&self, runs: &RunFiles, group: &[usize], buffer_size: usize, output: &mut W) -> io::Result<()> {
+        let mut readers = Vec::with_capacity(group.len());
+        let mut heap = BinaryHeap::with_capacity(group.len());
+        for (position, &run) in group.iter().enumerate() {
+            let mut reader = BufReader::with_capacity(buffer_size, File::open(runs.path(run))?);
+            if let Some(record) = self.codec.read(&mut reader)? {
+                heap.push(Reverse((record, position)));
+            }
+            readers.push(reader);
+        }
+
+        // Ties go to the earlier run, so equal records keep their input order.
+        while let Some(Reverse((record, position))) = heap.pop() {
+            self.codec.write(&record, output)?;
+            if let Some(next) = self.codec.read(&mut readers[position])? {
+                heap.push(Reverse((next, position)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A temporary directory holding run files, removed when dropped.
+struct RunFiles {
+    dir: PathBuf,
+    count: usize,
+}
+
+impl RunFiles {
+    fn create(parent: &Path) -> io::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let dir = parent.join(format!("external-sort-{}-{}", std::process::id(), id));
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, count: 0 })
+    }
+
+    /// The path of a new run.
+    fn next_path(&mut self) -> PathBuf {
+        self.count += 1;
+        self.path(self.count - 1)
+    }
+
+    fn path(&self, run: usize) -> PathBuf {
+        self.dir.join(format!("run-{}", run))
+    }
+}
+
+impl Drop for RunFiles {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::HeapSorter;
+    use std::io::Cursor;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("design-patterns-rs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn words(len: usize) -> Vec<String> {
+        let mut state: u32 = 2_463_534_242;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                format!("word{}", state % 5000)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorts_lines_through_run_files() {
+        let dir = scratch_dir("lines");
+        let words = words(2000);
+        let input = words.join("\n");
+        let mut output = Vec::new();
+
+        let report = ExternalSorter::new(LineCodec)
+            .with_memory_budget(4096)
+            .with_temp_dir(&dir)
+            .sort(input.as_bytes(), &mut output)
+            .unwrap();
+
+        let mut expected = words;
+        expected.sort();
+        assert_eq!(String::from_utf8(output).unwrap(), expected.join("\n") + "\n");
+        assert_eq!(report.records, 2000);
+        assert!(report.runs > 10);
+        // The budget only leaves room for 7 run buffers and the output's.
+        assert_eq!(report.merge_passes, 2);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn sorts_in_memory_when_the_budget_allows() {
+        let mut output = Vec::new();
+
+        let report = ExternalSorter::new(LineCodec).sort("pear\r\nfig\napple".as_bytes(), &mut output).unwrap();
+
+        assert_eq!(output, b"apple\nfig\npear\n");
+        assert_eq!(report, ExternalSortReport { records: 3, runs: 0, merge_passes: 0 });
+    }
+
+    #[test]
+    fn sorts_length_prefixed_records() {
+        let dir = scratch_dir("binary");
+        let records: Vec<Vec<u8>> = vec![vec![3, 1], vec![], vec![0, 255, 7], vec![3], vec![0, 255]];
+        let mut input = Vec::new();
+        for record in &records {
+            LengthPrefixedCodec.write(record, &mut input).unwrap();
+        }
+        let mut output = Vec::new();
+
+        let report = ExternalSorter::new(LengthPrefixedCodec)
+            .with_memory_budget(1)
+            .with_temp_dir(&dir)
+            .with_strategy::<HeapSorter>()
+            .sort(&input[..], &mut output)
+            .unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut sorted = Vec::new();
+        while let Some(record) = LengthPrefixedCodec.read(&mut reader).unwrap() {
+            sorted.push(record);
+        }
+        let mut expected = records;
+        expected.sort();
+        assert_eq!(sorted, expected);
+        assert_eq!(report.runs, 5);
+        assert_eq!(report.merge_passes, 3);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    // Length-prefixed records ordered by their first byte only.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Keyed(Vec<u8>);
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Keyed {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0[0].cmp(&other.0[0])
+        }
+    }
+
+    struct KeyedCodec;
+
+    impl RecordCodec for KeyedCodec {
+        type Record = Keyed;
+
+        fn write<W: Write>(&self, record: &Self::Record, writer: &mut W) -> io::Result<()> {
+            LengthPrefixedCodec.write(&record.0, writer)
+        }
+
+        fn read<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>> {
+            Ok(LengthPrefixedCodec.read(reader)?.map(Keyed))
+        }
+
+        fn size_of(&self, record: &Self::Record) -> usize {
+            LengthPrefixedCodec.size_of(&record.0)
+        }
+    }
+
+    #[test]
+    fn merges_in_several_passes_keeping_equal_records_in_order() {
+        let dir = scratch_dir("fan-in");
+        let records: Vec<Keyed> = (0..2000u16).map(|i| Keyed(vec![(i % 3) as u8, (i >> 8) as u8, i as u8])).collect();
+        let mut input = Vec::new();
+        for record in &records {
+            KeyedCodec.write(record, &mut input).unwrap();
+        }
+        let mut output = Vec::new();
+
+        let report = ExternalSorter::new(KeyedCodec)
+            .with_memory_budget(4096)
+            .with_max_fan_in(3)
+            .with_temp_dir(&dir)
+            .sort(&input[..], &mut output)
+            .unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut sorted = Vec::new();
+        while let Some(record) = KeyedCodec.read(&mut reader).unwrap() {
+            sorted.push(record.0);
+        }
+        let mut expected: Vec<Vec<u8>> = records.into_iter().map(|record| record.0).collect();
+        expected.sort_by_key(|record| record[0]);
+        assert_eq!(sorted, expected);
+        assert_eq!(report.runs, 14);
+        // Into 5 runs, then 2, then the output.
+        assert_eq!(report.merge_passes, 3);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn does_not_trust_record_lengths() {
+        let mut input = u64::MAX.to_le_bytes().to_vec();
+        input.extend_from_slice(&[1, 2, 3]);
+
+        let error = ExternalSorter::new(LengthPrefixedCodec).sort(&input[..], io::sink()).err().unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn fails_on_truncated_records() {
+        let mut input = Vec::new();
+        LengthPrefixedCodec.write(&vec![1, 2, 3], &mut input).unwrap();
+        input.pop();
+
+        let error = ExternalSorter::new(LengthPrefixedCodec).sort(&input[..], io::sink()).err().unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn sorts_empty_input() {
+        let mut output = Vec::new();
+
+        let report = ExternalSorter::new(LineCodec).sort(io::empty(), &mut output).unwrap();
+
+        assert!(output.is_empty());
+        assert_eq!(report.records, 0);
+    }
+}
@@ -0,0 +1,237 @@
+// A decorator measuring the work a sorting strategy does. Comparisons are counted
+// by wrapping the comparator, swaps and passes through the `SortObserver` every
+// strategy reports to, so all three are known for any strategy. Strategies picked
+// at runtime are measured through `DynSortingStrategy`, which unlike the static
+// `SortingStrategy` has an instance to keep the stats in.
+
+use crate::strategy::*;
+use crate::strategy_dyn_dispatch::DynSortingStrategy;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SortStats {
+    pub strategy: &'static str,
+    pub len: usize,
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub passes: u64,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for SortStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} sorted {} elements with {} comparisons, {} swaps, {} passes in {:?}",
+            self.strategy, self.len, self.comparisons, self.swaps, self.passes, self.elapsed
+        )
+    }
+}
+
+#[derive(Default)]
+struct Counter {
+    swaps: u64,
+    passes: u64,
+}
+
+impl SortObserver for Counter {
+    fn swapped(&mut self) {
+        self.swaps += 1;
+    }
+
+    fn pass_finished(&mut self) {
+        self.passes += 1;
+    }
+}
+
+/// Sorts with `S`, like `sort_with_strategy`, returning what it did.
+pub fn sort_with_stats<S: SortingStrategy, T: PartialOrd>(list: &mut [T]) -> SortStats {
+    Instrumented::<S>::sort_with_stats(list)
+}
+
+/// Sorts with `S`, measuring what it does.
+///
+/// The `_with_stats` functions return the measurements. An `Instrumented` value is
+/// also a `DynSortingStrategy`, e.g. for a `Sorter`, keeping the stats of its last
+/// sort for `last_stats`. Clones share them, so a clone kept aside reads what the
+/// one handed to the `Sorter` measured.
+pub struct Instrumented<S> {
+    last: Rc<Cell<Option<SortStats>>>,
+    strategy: PhantomData<S>,
+}
+
+impl<S> Instrumented<S> {
+    pub fn new() -> Self {
+        Self { last: Rc::default(), strategy: PhantomData }
+    }
+
+    /// The stats of the last sort made through `DynSortingStrategy::sort`.
+    pub fn last_stats(&self) -> Option<SortStats> {
+        self.last.get()
+    }
+}
+
+impl<S> Clone for Instrumented<S> {
+    fn clone(&self) -> Self {
+        Self { last: Rc::clone(&self.last), strategy: PhantomData }
+    }
+}
+
+impl<S> Default for Instrumented<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SortingStrategy> Instrumented<S> {
+    pub fn sort_with_stats<T: PartialOrd>(list: &mut [T]) -> SortStats {
+        Self::sort_by_with_stats(list, incomparable_last_after::<T>(S::REVERSES))
+    }
+
+    pub fn sort_by_with_stats<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], mut compare: F) -> SortStats {
+        let len = list.len();
+        let mut comparisons = 0;
+        let mut counter = Counter::default();
+        let start = Instant::now();
+        S::sort_by_observed(
+            list,
+            |a, b| {
+                comparisons += 1;
+                compare(a, b)
+            },
+            &mut counter,
+        );
+        let elapsed = start.elapsed();
+
+        SortStats {
+            strategy: S::NAME,
+            len,
+            comparisons,
+            swaps: counter.swaps,
+            passes: counter.passes,
+            elapsed,
+        }
+    }
+}
+
+impl<S: SortingStrategy, T: PartialOrd> DynSortingStrategy<T> for Instrumented<S> {
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn is_stable(&self) -> bool {
+        S::IS_STABLE
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        self.last.set(Some(Self::sort_with_stats(list)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random(len: usize) -> Vec<u32> {
+        let mut state: u32 = 2_463_534_242;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state % 1000
+            })
+            .collect()
+    }
+
+    #[test]
+    fn optimized_bubble_sort_compares_less() {
+        let mut list = pseudo_random(200);
+        let bubble = Instrumented::<BubbleSorter>::sort_with_stats(&mut list.clone());
+        let optimized = Instrumented::<OptimizedBubbleSorter>::sort_with_stats(&mut list);
+
+        assert!(optimized.comparisons < bubble.comparisons);
+        assert_eq!(optimized.swaps, bubble.swaps);
+        assert!(optimized.passes <= bubble.passes);
+        assert!(list.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn counts_the_work_on_a_known_input() {
+        let stats = Instrumented::<BubbleSorter>::sort_with_stats(&mut [3, 2, 1]);
+
+        assert_eq!(stats.comparisons, 6);
+        assert_eq!(stats.swaps, 3);
+        assert_eq!(stats.passes, 3);
+
+        let stats = Instrumented::<OptimizedBubbleSorter>::sort_with_stats(&mut [1, 2, 3, 4]);
+
+        assert_eq!(stats.comparisons, 3);
+        assert_eq!(stats.swaps, 0);
+        assert_eq!(stats.passes, 1);
+    }
+
+    #[test]
+    fn counts_swaps_and_passes_for_every_strategy() {
+        fn stats<S: SortingStrategy>(list: &mut [u32]) -> SortStats {
+            let stats = Instrumented::<S>::sort_by_with_stats(list, |a, b| a.cmp(b));
+            assert!(list.windows(2).all(|pair| pair[0] <= pair[1]), "{} didn't sort", S::NAME);
+            stats
+        }
+
+        let list = pseudo_random(64);
+        for stats in [
+            stats::<InsertionSorter>(&mut list.clone()),
+            stats::<MergeSorter>(&mut list.clone()),
+            stats::<QuickSorter>(&mut list.clone()),
+            stats::<HeapSorter>(&mut list.clone()),
+            stats::<ShellSorter>(&mut list.clone()),
+            stats::<IntroSorter>(&mut list.clone()),
+            stats::<TimSorter>(&mut list.clone()),
+        ] {
+            assert!(stats.comparisons > 0 && stats.swaps > 0 && stats.passes > 0, "{}", stats);
+        }
+
+        // Sorted input never moves, but merge sort still merges every pair of halves.
+        let sorted = stats::<MergeSorter>(&mut (0..64).collect::<Vec<_>>());
+        assert_eq!(sorted.swaps, 0);
+        assert_eq!(sorted.passes, 63);
+        assert!(sorted.to_string().starts_with("merge sorted 64 elements with "));
+    }
+
+    #[test]
+    fn decorates_strategies_chosen_elsewhere() {
+        let mut list = vec![2.0, 1.0, 3.0];
+
+        let stats = sort_with_stats::<Reversed<OptimizedBubbleSorter>, f64>(&mut list);
+
+        assert_eq!(list, vec![3.0, 2.0, 1.0]);
+        assert_eq!(stats.strategy, "reversed_optimized_bubble");
+        assert_eq!(stats.swaps, 2);
+        assert!(stats.to_string().contains("3 elements with 3 comparisons, 2 swaps, 3 passes in "));
+    }
+
+    #[test]
+    fn keeps_the_stats_of_sorts_made_through_a_sorter() {
+        use crate::strategy_dyn_dispatch::Sorter;
+
+        let instrumented = Instrumented::<HeapSorter>::new();
+        let sorter = Sorter::new(Box::new(instrumented.clone()));
+        assert_eq!(instrumented.last_stats(), None);
+
+        let mut list = pseudo_random(100);
+        sorter.sort(&mut list);
+
+        let stats = instrumented.last_stats().unwrap();
+        assert!(list.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!((stats.strategy, stats.len), ("heap", 100));
+        assert!(stats.comparisons > 0 && stats.swaps > 0);
+        assert_eq!(stats.passes, 2);
+        assert_eq!(sorter.strategy().name(), "heap");
+    }
+}
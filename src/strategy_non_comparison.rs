@@ -0,0 +1,521 @@
+// Sorts that take keys apart instead of comparing them, and so only work for keys
+// that can be taken apart: `RadixKey`s for integers, floats and chars, and byte
+// strings for `MsdRadixSorter`. They can't be `SortingStrategy`s, which accept any
+// `PartialOrd` type, but they are `DynSortingStrategy`s for the types they support,
+// so they can be picked by name like every other strategy.
+//
+// Elements are never cloned: the sorts order `(key, index)` pairs, then move every
+// element to its final place in one pass.
+
+use crate::strategy::{apply_permutation, InsertionSorter, MergeSorter, SortingStrategy};
+use crate::strategy_dyn_dispatch::{strategy_by_name, DynSortingStrategy, UnknownStrategy};
+use std::mem;
+
+// Subproblems shorter than this are finished with insertion sort.
+const INSERTION_THRESHOLD: usize = 16;
+
+/// A key that maps to a `u64` with the same order.
+pub trait RadixKey: Copy {
+    fn radix(self) -> u64;
+}
+
+macro_rules! unsigned_radix_key {
+    ($($key:ty),*) => {
+        $(impl RadixKey for $key {
+            fn radix(self) -> u64 {
+                self as u64
+            }
+        })*
+    };
+}
+
+macro_rules! signed_radix_key {
+    ($($key:ty),*) => {
+        $(impl RadixKey for $key {
+            fn radix(self) -> u64 {
+                // Flipping the sign bit puts negative numbers first.
+                (self as i64 as u64) ^ (1 << 63)
+            }
+        })*
+    };
+}
+
+unsigned_radix_key!(u8, u16, u32, u64, usize);
+signed_radix_key!(i8, i16, i32, i64, isize);
+
+impl RadixKey for bool {
+    fn radix(self) -> u64 {
+        self as u64
+    }
+}
+
+impl RadixKey for char {
+    fn radix(self) -> u64 {
+        self as u64
+    }
+}
+
+/// NaN sorts last, like `SortingStrategy::sort` orders it, and -0.0 before 0.0.
+impl RadixKey for f64 {
+    fn radix(self) -> u64 {
+        if self.is_nan() {
+            return u64::MAX;
+        }
+        let bits = self.to_bits();
+        if bits >> 63 == 1 {
+            !bits
+        } else {
+            bits | (1 << 63)
+        }
+    }
+}
+
+impl RadixKey for f32 {
+    fn radix(self) -> u64 {
+        (self as f64).radix()
+    }
+}
+
+/// A strategy that only sorts the element types it can take apart.
+pub trait NonComparisonSortingStrategy<T> {
+    const NAME: &'static str;
+    const IS_STABLE: bool;
+
+    fn sort(list: &mut [T]);
+}
+
+/// Stable. O(w·n) for keys of `w` bytes, O(n) extra space.
+///
+/// Sorts one byte at a time from the least significant, skipping bytes that are
+/// the same in every key.
+pub struct LsdRadixSorter;
+
+impl LsdRadixSorter {
+    pub fn sort_by_key<T, K: RadixKey, F: FnMut(&T) -> K>(list: &mut [T], key: F) {
+        let mut keyed = radix_keys(list, key);
+        lsd_radix_sort(&mut keyed);
+        apply_order(list, &keyed);
+    }
+}
+
+/// Stable. O(n + k) for keys spanning `k` values, O(n + k) extra space.
+///
+/// Falls back to `LsdRadixSorter` when keys span many more values than there are
+/// elements.
+pub struct CountingSorter;
+
+impl CountingSorter {
+    pub fn sort_by_key<T, K: RadixKey, F: FnMut(&T) -> K>(list: &mut [T], key: F) {
+        let mut keyed = radix_keys(list, key);
+        counting_sort(&mut keyed);
+        apply_order(list, &keyed);
+    }
+}
+
+/// Stable. O(n) on evenly spread keys, O(n log n) when they cluster, O(n) extra space.
+///
+/// Spreads keys over `n` buckets by value and insertion sorts each bucket, or
+/// merge sorts it when outliers stretch the range and crowd most keys into it.
+pub struct BucketSorter;
+
+impl BucketSorter {
+    pub fn sort_by_key<T, K: RadixKey, F: FnMut(&T) -> K>(list: &mut [T], key: F) {
+        let mut keyed = radix_keys(list, key);
+        bucket_sort(&mut keyed);
+        apply_order(list, &keyed);
+    }
+}
+
+/// Stable. O(l) for byte strings of total length `l`, O(n) extra space.
+///
+/// Sorts byte strings in lexicographic order, one byte at a time from the first,
+/// which for UTF-8 strings is also the order of their chars.
+pub struct MsdRadixSorter;
+
+impl MsdRadixSorter {
+    pub fn sort_by_key<T, F: Fn(&T) -> &[u8]>(list: &mut [T], key: F) {
+        let mut indices: Vec<usize> = (0..list.len()).collect();
+        {
+            let keys: Vec<&[u8]> = list.iter().map(&key).collect();
+            let mut buffer = indices.clone();
+            msd_radix_sort(&keys, &mut indices, &mut buffer);
+        }
+        apply_permutation(list, &mut indices, &mut ());
+    }
+}
+
+macro_rules! radix_key_strategies {
+    ($($strategy:ident => $name:expr),*) => {
+        $(
+            impl<T: RadixKey> NonComparisonSortingStrategy<T> for $strategy {
+                const NAME: &'static str = $name;
+                const IS_STABLE: bool = true;
+
+                fn sort(list: &mut [T]) {
+                    Self::sort_by_key(list, |key| *key);
+                }
+            }
+
+            impl<T: RadixKey> DynSortingStrategy<T> for $strategy {
+                fn name(&self) -> &'static str {
+                    <Self as NonComparisonSortingStrategy<T>>::NAME
+                }
+
+                fn is_stable(&self) -> bool {
+                    <Self as NonComparisonSortingStrategy<T>>::IS_STABLE
+                }
+
+                fn sort(&self, list: &mut [T]) {
+                    <Self as NonComparisonSortingStrategy<T>>::sort(list);
+                }
+            }
+        )*
+    };
+}
+
+radix_key_strategies!(LsdRadixSorter => "lsd_radix", CountingSorter => "counting", BucketSorter => "bucket");
+
+impl<T: AsRef<[u8]>> NonComparisonSortingStrategy<T> for MsdRadixSorter {
+    const NAME: &'static str = "msd_radix";
+    const IS_STABLE: bool = true;
+
+    fn sort(list: &mut [T]) {
+        Self::sort_by_key(list, |bytes| bytes.as_ref());
+    }
+}
+
+impl<T: AsRef<[u8]>> DynSortingStrategy<T> for MsdRadixSorter {
+    fn name(&self) -> &'static str {
+        <Self as NonComparisonSortingStrategy<T>>::NAME
+    }
+
+    fn is_stable(&self) -> bool {
+        <Self as NonComparisonSortingStrategy<T>>::IS_STABLE
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        <Self as NonComparisonSortingStrategy<T>>::sort(list);
+    }
+}
+
+/// Like `strategy_by_name`, also knowing the strategies for `RadixKey`s.
+pub fn radix_key_strategy_by_name<T: RadixKey + PartialOrd + 'static>(name: &str) -> Result<Box<dyn DynSortingStrategy<T>>, UnknownStrategy> {
+    let strategy: Box<dyn DynSortingStrategy<T>> = match name {
+        "lsd_radix" => Box::new(LsdRadixSorter),
+        "counting" => Box::new(CountingSorter),
+        "bucket" => Box::new(BucketSorter),
+        _ => return strategy_by_name(name),
+    };
+    Ok(strategy)
+}
+
+/// Like `strategy_by_name`, also knowing the strategies for byte strings.
+pub fn byte_string_strategy_by_name<T: AsRef<[u8]> + PartialOrd + 'static>(name: &str) -> Result<Box<dyn DynSortingStrategy<T>>, UnknownStrategy> {
+    match name {
+        "msd_radix" => Ok(Box::new(MsdRadixSorter)),
+        _ => strategy_by_name(name),
+    }
+}
+
+fn radix_keys<T, K: RadixKey, F: FnMut(&T) -> K>(list: &[T], mut key: F) -> Vec<(u64, usize)> {
+    list.iter().enumerate().map(|(index, element)| (key(element).radix(), index)).collect()
+}
+
+fn apply_order<T>(list: &mut [T], keyed: &[(u64, usize)]) {
+    let mut indices: Vec<usize> = keyed.iter().map(|&(_, index)| index).collect();
+    apply_permutation(list, &mut indices, &mut ());
+}
+
+fn lsd_radix_sort(keyed: &mut Vec<(u64, usize)>) {
+    let mut buffer = keyed.clone();
+    for shift in (0..64).step_by(8) {
+        let mut offsets = [0; 256];
+        for &(key, _) in keyed.iter() {
+            offsets[(key >> shift) as usize & 0xff] += 1;
+        }
+        if offsets.contains(&keyed.len()) {
+            continue;
+        }
+
+        let mut total = 0;
+        for offset in offsets.iter_mut() {
+            total += mem::replace(offset, total);
+        }
+        for &entry in keyed.iter() {
+            let byte = (entry.0 >> shift) as usize & 0xff;
+            buffer[offsets[byte]] = entry;
+            offsets[byte] += 1;
+        }
+        mem::swap(keyed, &mut buffer);
+    }
+}
+
+fn counting_sort(keyed: &mut Vec<(u64, usize)>) {
+    let (min, max) = match (keyed.iter().map(|entry| entry.0).min(), keyed.iter().map(|entry| entry.0).max()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return,
+    };
+    if max - min > (keyed.len() as u64 * 4).max(1 << 16) {
+        lsd_radix_sort(keyed);
+        return;
+    }
+
+    let mut offsets = vec![0; (max - min) as usize + 1];
+    for &(key, _) in keyed.iter() {
+        offsets[(key - min) as usize] += 1;
+    }
+    let mut total = 0;
+    for offset in offsets.iter_mut() {
+        total += mem::replace(offset, total);
+    }
+    let mut sorted = keyed.clone();
+    for &entry in keyed.iter() {
+        let slot = &mut offsets[(entry.0 - min) as usize];
+        sorted[*slot] = entry;
+        *slot += 1;
+    }
+    *keyed = sorted;
+}
+
+fn bucket_sort(keyed: &mut Vec<(u64, usize)>) {
+    let (min, max) = match (keyed.iter().map(|entry| entry.0).min(), keyed.iter().map(|entry| entry.0).max()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return,
+    };
+
+    let len = keyed.len();
+    let span = (max - min) as u128 + 1;
+    let mut buckets = vec![Vec::new(); len];
+    for &entry in keyed.iter() {
+        buckets[((entry.0 - min) as u128 * len as u128 / span) as usize].push(entry);
+    }
+
+    keyed.clear();
+    for mut bucket in buckets {
+        let by_key = |a: &(u64, usize), b: &(u64, usize)| a.0.cmp(&b.0);
+        if bucket.len() <= INSERTION_THRESHOLD {
+            InsertionSorter::sort_by(&mut bucket, by_key);
+        } else {
+            MergeSorter::sort_by(&mut bucket, by_key);
+        }
+        keyed.extend(bucket);
+    }
+}
+
+/// Sorts `indices` by the byte strings they point to in `keys`.
+///
+/// Buckets left to sort are kept on a stack rather than recursed into, since
+/// strings sharing long prefixes would otherwise need one frame per shared byte.
+fn msd_radix_sort(keys: &[&[u8]], indices: &mut [usize], buffer: &mut [usize]) {
+    // Ranges of `indices` whose strings share their first `depth` bytes.
+    let mut pending = vec![(0, indices.len(), 0)];
+    while let Some((start, end, depth)) = pending.pop() {
+        let (indices, buffer) = (&mut indices[start..end], &mut buffer[start..end]);
+        if indices.len() <= INSERTION_THRESHOLD {
+            InsertionSorter::sort_by(indices, |&a: &usize, &b: &usize| keys[a][depth..].cmp(&keys[b][depth..]));
+            continue;
+        }
+
+        // Bucket 0 holds the strings that end here, which sort first.
+        let bucket_of = |index: usize| keys[index].get(depth).map_or(0, |&byte| byte as usize + 1);
+        let mut offsets = [0; 257];
+        for &index in indices.iter() {
+            offsets[bucket_of(index)] += 1;
+        }
+        if offsets[1..].contains(&indices.len()) {
+            // Every string shares this byte too.
+            pending.push((start, end, depth + 1));
+            continue;
+        }
+
+        let mut total = 0;
+        for offset in offsets.iter_mut() {
+            total += mem::replace(offset, total);
+        }
+        let starts = offsets;
+        for &index in indices.iter() {
+            let bucket = bucket_of(index);
+            buffer[offsets[bucket]] = index;
+            offsets[bucket] += 1;
+        }
+        indices.copy_from_slice(buffer);
+
+        for bucket in 1..257 {
+            if offsets[bucket] - starts[bucket] > 1 {
+                pending.push((start + starts[bucket], start + offsets[bucket], depth + 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy_dyn_dispatch::Sorter;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Order {
+        customer: i32,
+        position: usize,
+    }
+
+    fn pseudo_random(len: usize) -> Vec<u32> {
+        let mut state: u32 = 2_463_534_242;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state
+            })
+            .collect()
+    }
+
+    fn assert_sorts_integers<S: NonComparisonSortingStrategy<i64> + NonComparisonSortingStrategy<u32>>() {
+        let mut unsigned = pseudo_random(2000);
+        let mut expected = unsigned.clone();
+        expected.sort();
+        S::sort(&mut unsigned);
+        assert_eq!(unsigned, expected);
+
+        let mut signed: Vec<i64> = pseudo_random(2000).into_iter().map(|value| value as i64 - (1 << 31)).collect();
+        signed.extend(&[i64::MIN, i64::MAX, 0, -1]);
+        let mut expected = signed.clone();
+        expected.sort();
+        S::sort(&mut signed);
+        assert_eq!(signed, expected);
+    }
+
+    type SortByKey = fn(&mut [Order], fn(&Order) -> i32);
+
+    fn assert_stable_by_key(sort_by_key: SortByKey) {
+        let mut orders: Vec<Order> = pseudo_random(500)
+            .into_iter()
+            .enumerate()
+            .map(|(position, value)| Order { customer: (value % 20) as i32 - 10, position })
+            .collect();
+        let mut expected = orders.clone();
+        expected.sort_by_key(|order| order.customer);
+
+        sort_by_key(&mut orders, |order| order.customer);
+
+        assert_eq!(orders, expected);
+    }
+
+    #[test]
+    fn lsd_radix_sort_works_and_is_stable() {
+        assert_sorts_integers::<LsdRadixSorter>();
+        assert_stable_by_key(LsdRadixSorter::sort_by_key);
+    }
+
+    #[test]
+    fn counting_sort_works_and_is_stable() {
+        assert_sorts_integers::<CountingSorter>();
+        assert_stable_by_key(CountingSorter::sort_by_key);
+
+        let mut small_range: Vec<u8> = pseudo_random(1000).into_iter().map(|value| value as u8).collect();
+        let mut expected = small_range.clone();
+        expected.sort();
+        <CountingSorter as NonComparisonSortingStrategy<u8>>::sort(&mut small_range);
+        assert_eq!(small_range, expected);
+    }
+
+    #[test]
+    fn bucket_sort_works_and_is_stable() {
+        assert_sorts_integers::<BucketSorter>();
+        assert_stable_by_key(BucketSorter::sort_by_key);
+    }
+
+    #[test]
+    fn bucket_sort_handles_outliers() {
+        // The outlier leaves every other order in the first bucket.
+        let mut orders: Vec<Order> = pseudo_random(5000)
+            .into_iter()
+            .enumerate()
+            .map(|(position, value)| Order { customer: (value % 100) as i32, position })
+            .collect();
+        orders.push(Order { customer: i32::MAX, position: 5000 });
+        let mut expected = orders.clone();
+        expected.sort_by_key(|order| order.customer);
+
+        BucketSorter::sort_by_key(&mut orders, |order| order.customer);
+
+        assert_eq!(orders, expected);
+    }
+
+    #[test]
+    fn sorts_floats_and_chars() {
+        let mut floats = vec![2.5, f64::NAN, -0.0, f64::NEG_INFINITY, 0.0, -3.75, f64::INFINITY, 1e-300];
+
+        <LsdRadixSorter as NonComparisonSortingStrategy<f64>>::sort(&mut floats);
+
+        assert_eq!(&floats[..7], &[f64::NEG_INFINITY, -3.75, -0.0, 0.0, 1e-300, 2.5, f64::INFINITY]);
+        assert!(floats[2].is_sign_negative() && floats[3].is_sign_positive());
+        assert!(floats[7].is_nan());
+
+        let mut chars: Vec<char> = "radix ß sort".chars().collect();
+        <BucketSorter as NonComparisonSortingStrategy<char>>::sort(&mut chars);
+        assert_eq!(chars.into_iter().collect::<String>(), "  adiorrstxß");
+    }
+
+    #[test]
+    fn msd_radix_sorts_byte_strings() {
+        let mut words: Vec<String> = pseudo_random(3000).into_iter().map(|value| format!("{:x}", value % 100_000)).collect();
+        words.extend(vec![String::new(), String::from("é"), String::from("e"), String::from("ab"), String::from("a")]);
+        words.extend(vec![String::from("same prefix"); 40]);
+        let mut expected = words.clone();
+        expected.sort();
+
+        <MsdRadixSorter as NonComparisonSortingStrategy<String>>::sort(&mut words);
+
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn msd_radix_sorts_strings_sharing_long_prefixes() {
+        // Each string is a prefix of the next, so every byte adds a level of buckets.
+        let mut words: Vec<String> = (0..3000).rev().map(|len| "a".repeat(len)).collect();
+
+        // A small stack, which one frame per level would overflow.
+        let sorted = std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                <MsdRadixSorter as NonComparisonSortingStrategy<String>>::sort(&mut words);
+                words
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(sorted.iter().enumerate().all(|(len, word)| word.len() == len));
+    }
+
+    #[test]
+    fn msd_radix_sorts_by_byte_key() {
+        let mut files = vec![("b.rs", 2), ("a.rs", 1), ("b.rs", 0), ("a.rs", 3)];
+
+        MsdRadixSorter::sort_by_key(&mut files, |file| file.0.as_bytes());
+
+        assert_eq!(files, vec![("a.rs", 1), ("a.rs", 3), ("b.rs", 2), ("b.rs", 0)]);
+    }
+
+    #[test]
+    fn is_picked_by_name_with_the_other_strategies() {
+        for &name in &["lsd_radix", "counting", "bucket", "merge", "optimized_bubble"] {
+            let sorter: Sorter<i32> = Sorter::new(radix_key_strategy_by_name(name).unwrap());
+            let mut list = vec![3, -7, 2, 1];
+
+            sorter.sort(&mut list);
+
+            assert_eq!(sorter.strategy().name(), name);
+            assert_eq!(list, vec![-7, 1, 2, 3]);
+        }
+
+        let strategy = byte_string_strategy_by_name::<&str>("msd_radix").unwrap();
+        let mut list = vec!["pear", "fig", "apple"];
+        strategy.sort(&mut list);
+        assert_eq!(list, vec!["apple", "fig", "pear"]);
+        assert!(byte_string_strategy_by_name::<&str>("bubble").is_ok());
+        assert_eq!(radix_key_strategy_by_name::<u8>("msd_radix").err(), Some(UnknownStrategy(String::from("msd_radix"))));
+    }
+}
@@ -0,0 +1,340 @@
+// Sorting strategies that split the list across threads with `std::thread::scope`.
+// Sending parts of a list to other threads needs `T: Send` and a `Sync` comparator,
+// which `SortingStrategy` can't ask of its callers. The parallel sorters therefore
+// can't be used with `sort_with_strategy`. They implement `ParallelSortingStrategy`
+// instead, whose methods ask for both, and are swapped in through
+// `par_sort_with_strategy`. Sequential strategies implement it too, sorting on the
+// calling thread. For element types that are `Send`, they are also
+// `DynSortingStrategy`s, which `par_strategy_by_name` looks up by name along with
+// every sequential strategy.
+//
+// The thread count and the length below which a part is sorted sequentially are
+// const parameters, e.g. `ParallelMergeSorter<8, 10_000>`. A thread count of 0
+// uses every available core.
+
+use crate::strategy::*;
+use crate::strategy_dyn_dispatch::{strategy_by_name, DynSortingStrategy, UnknownStrategy};
+use std::cmp::Ordering;
+use std::thread;
+
+pub trait ParallelSortingStrategy {
+    /// Identifies the strategy, like `SortingStrategy::NAME`.
+    const NAME: &'static str;
+    /// Whether equal elements keep their relative order.
+    const IS_STABLE: bool;
+    /// Like `SortingStrategy::REVERSES`.
+    const REVERSES: bool = false;
+
+    /// Sorts with `compare`, which must describe a total order.
+    fn par_sort_by<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(list: &mut [T], compare: F);
+
+    /// Sorts like `SortingStrategy::sort`, ordering incomparable values such as NaN last.
+    fn par_sort<T: PartialOrd + Send>(list: &mut [T]) {
+        Self::par_sort_by(list, incomparable_last_after::<T>(Self::REVERSES));
+    }
+}
+
+pub fn par_sort_with_strategy<S: ParallelSortingStrategy, T: PartialOrd + Send>(list: &mut [T]) {
+    S::par_sort(list);
+}
+
+macro_rules! sequential {
+    ($($strategy:ty),*) => {
+        $(
+            /// Sorts on the calling thread.
+            impl ParallelSortingStrategy for $strategy {
+                const NAME: &'static str = <$strategy as SortingStrategy>::NAME;
+                const IS_STABLE: bool = <$strategy as SortingStrategy>::IS_STABLE;
+                const REVERSES: bool = <$strategy as SortingStrategy>::REVERSES;
+
+                fn par_sort_by<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(list: &mut [T], compare: F) {
+                    <$strategy as SortingStrategy>::sort_by(list, compare);
+                }
+            }
+        )*
+    };
+}
+
+sequential!(
+    BubbleSorter,
+    OptimizedBubbleSorter,
+    InsertionSorter,
+    MergeSorter,
+    QuickSorter,
+    HeapSorter,
+    ShellSorter,
+    IntroSorter,
+    TimSorter
+);
+
+impl<S: ParallelSortingStrategy> ParallelSortingStrategy for Reversed<S> {
+    const NAME: &'static str = trim_padding(&prefixed_name("reversed_", S::NAME));
+    const IS_STABLE: bool = S::IS_STABLE;
+    const REVERSES: bool = !S::REVERSES;
+
+    fn par_sort_by<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(list: &mut [T], compare: F) {
+        S::par_sort_by(list, |a, b| compare(b, a));
+    }
+}
+
+macro_rules! dyn_parallel {
+    ($($strategy:ident),*) => {
+        $(
+            impl<T, const THREADS: usize, const CUTOFF: usize> DynSortingStrategy<T> for $strategy<THREADS, CUTOFF>
+            where
+                T: PartialOrd + Send,
+            {
+                fn name(&self) -> &'static str {
+                    Self::NAME
+                }
+
+                fn is_stable(&self) -> bool {
+                    Self::IS_STABLE
+                }
+
+                fn sort(&self, list: &mut [T]) {
+                    Self::par_sort(list);
+                }
+            }
+        )*
+    };
+}
+
+dyn_parallel!(ParallelMergeSorter, ParallelQuickSorter);
+
+/// Names of the parallel strategies `par_strategy_by_name` knows about, on top of
+/// `strategy_dyn_dispatch::STRATEGY_NAMES`.
+pub const PARALLEL_STRATEGY_NAMES: &[&str] = &[<ParallelMergeSorter>::NAME, <ParallelQuickSorter>::NAME];
+
+/// Like `strategy_by_name`, also knowing the parallel strategies, with their
+/// default thread count and cutoff.
+pub fn par_strategy_by_name<T: PartialOrd + Send + 'static>(name: &str) -> Result<Box<dyn DynSortingStrategy<T>>, UnknownStrategy> {
+    let strategy: Box<dyn DynSortingStrategy<T>> = match name {
+        <ParallelMergeSorter>::NAME => Box::<ParallelMergeSorter>::new(ParallelMergeSorter),
+        <ParallelQuickSorter>::NAME => Box::<ParallelQuickSorter>::new(ParallelQuickSorter),
+        _ => return strategy_by_name(name),
+    };
+    Ok(strategy)
+}
+
+fn thread_count(threads: usize) -> usize {
+    if threads > 0 {
+        return threads;
+    }
+    thread::available_parallelism().map(|count| count.get()).unwrap_or(1)
+}
+
+/// Stable. Sorts the two halves of the list on separate threads, then merges them.
+/// Gives the same result as `MergeSorter`.
+pub struct ParallelMergeSorter<const THREADS: usize = 0, const CUTOFF: usize = 4096>;
+
+impl<const THREADS: usize, const CUTOFF: usize> ParallelSortingStrategy for ParallelMergeSorter<THREADS, CUTOFF> {
+    const NAME: &'static str = "parallel_merge";
+    const IS_STABLE: bool = true;
+
+    fn par_sort_by<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(list: &mut [T], compare: F) {
+        parallel_merge_sort(list, &compare, thread_count(THREADS), CUTOFF);
+    }
+}
+
+fn parallel_merge_sort<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(v: &mut [T], compare: &F, threads: usize, cutoff: usize) {
+    if threads <= 1 || v.len() <= cutoff || v.len() < 2 {
+        MergeSorter::sort_by(v, compare);
+        return;
+    }
+
+    let mid = v.len() / 2;
+    let (left, right) = v.split_at_mut(mid);
+    thread::scope(|scope| {
+        scope.spawn(|| parallel_merge_sort(left, compare, threads / 2, cutoff));
+        parallel_merge_sort(right, compare, threads - threads / 2, cutoff);
+    });
+
+    // Merging indices keeps the merge stable without cloning elements.
+    let mut indices: Vec<usize> = (0..v.len()).collect();
+    let mut buffer = indices.clone();
+    {
+        let elements = &*v;
+        merge(&mut indices, mid, &mut buffer, &mut |&a: &usize, &b: &usize| {
+            compare(&elements[a], &elements[b]) == Ordering::Less
+        });
+    }
+    apply_permutation(v, &mut indices, &mut ());
+}
+
+/// Unstable. Partitions the list, then sorts both sides on separate threads.
+/// Sorts like `QuickSorter`, though equal elements may end up in another order.
+pub struct ParallelQuickSorter<const THREADS: usize = 0, const CUTOFF: usize = 4096>;
+
+impl<const THREADS: usize, const CUTOFF: usize> ParallelSortingStrategy for ParallelQuickSorter<THREADS, CUTOFF> {
+    const NAME: &'static str = "parallel_quick";
+    const IS_STABLE: bool = false;
+
+    fn par_sort_by<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(list: &mut [T], compare: F) {
+        parallel_quicksort(list, &compare, thread_count(THREADS), CUTOFF);
+    }
+}
+
+fn parallel_quicksort<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(v: &mut [T], compare: &F, threads: usize, cutoff: usize) {
+    let mut is_less = |a: &T, b: &T| compare(a, b) == Ordering::Less;
+    if threads <= 1 || v.len() <= cutoff || v.len() < 2 {
+        quicksort(v, &mut is_less, None, &mut ());
+        return;
+    }
+
+    let pivot = partition(v, &mut is_less, &mut ());
+    let (left, right) = v.split_at_mut(pivot);
+    let right = &mut right[1..];
+    thread::scope(|scope| {
+        scope.spawn(|| parallel_quicksort(left, compare, threads / 2, cutoff));
+        parallel_quicksort(right, compare, threads - threads / 2, cutoff);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Record {
+        key: u32,
+        position: usize,
+    }
+
+    fn records(len: usize) -> Vec<Record> {
+        let mut state: u32 = 2_463_534_242;
+        (0..len)
+            .map(|position| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                Record { key: state % 1000, position }
+            })
+            .collect()
+    }
+
+    fn by_key(a: &Record, b: &Record) -> Ordering {
+        a.key.cmp(&b.key)
+    }
+
+    fn positions(list: &[Record]) -> Vec<usize> {
+        list.iter().map(|record| record.position).collect()
+    }
+
+    #[test]
+    fn parallel_merge_sort_matches_merge_sort() {
+        let mut expected = records(50_000);
+        MergeSorter::sort_by(&mut expected, by_key);
+
+        let mut list = records(50_000);
+        ParallelMergeSorter::<4, 1000>::par_sort_by(&mut list, by_key);
+        assert_eq!(positions(&list), positions(&expected));
+
+        let mut list = records(50_000);
+        ParallelMergeSorter::<0, 16>::par_sort_by(&mut list, by_key);
+        assert_eq!(positions(&list), positions(&expected));
+    }
+
+    fn assert_par_sorts<S: ParallelSortingStrategy>() {
+        let mut list = records(20_000);
+
+        S::par_sort_by(&mut list, by_key);
+
+        assert!(list.windows(2).all(|pair| pair[0].key <= pair[1].key));
+        let mut positions = positions(&list);
+        positions.sort();
+        assert_eq!(positions, (0..20_000).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn parallel_quicksort_sorts() {
+        assert_par_sorts::<ParallelQuickSorter>();
+        assert_par_sorts::<ParallelQuickSorter<3, 100>>();
+        assert_par_sorts::<ParallelQuickSorter<8, 0>>();
+        assert_par_sorts::<ParallelQuickSorter<1, 0>>();
+    }
+
+    fn assert_sorts_like<P: ParallelSortingStrategy, S: SortingStrategy>() {
+        // Duplicates, with a NaN every few values.
+        let input: Vec<f64> = records(5_000)
+            .iter()
+            .map(|record| if record.key % 7 == 0 { f64::NAN } else { (record.key % 50) as f64 - 25.0 })
+            .collect();
+        let bits = |list: &[f64]| list.iter().map(|value| value.to_bits()).collect::<Vec<u64>>();
+
+        let mut expected = input.clone();
+        S::sort(&mut expected);
+        let mut list = input;
+        par_sort_with_strategy::<P, f64>(&mut list);
+
+        assert_eq!(bits(&list), bits(&expected), "{} doesn't sort like {}", P::NAME, S::NAME);
+        assert!(list.last().unwrap().is_nan());
+    }
+
+    #[test]
+    fn swaps_in_for_sequential_strategies() {
+        assert_sorts_like::<ParallelMergeSorter<2, 0>, MergeSorter>();
+        assert_sorts_like::<ParallelMergeSorter<4, 100>, MergeSorter>();
+        assert_sorts_like::<ParallelQuickSorter<2, 0>, QuickSorter>();
+        assert_sorts_like::<ParallelQuickSorter<4, 100>, QuickSorter>();
+        assert_sorts_like::<HeapSorter, HeapSorter>();
+        assert_sorts_like::<Reversed<ParallelMergeSorter<4, 100>>, Reversed<MergeSorter>>();
+    }
+
+    #[test]
+    fn looks_up_parallel_strategies_by_name() {
+        use crate::strategy_dyn_dispatch::{Sorter, STRATEGY_NAMES};
+
+        let mut sorter: Sorter<f64> = Sorter::new(par_strategy_by_name("merge").unwrap());
+        for &name in PARALLEL_STRATEGY_NAMES.iter().chain(STRATEGY_NAMES) {
+            let previous = sorter.set_strategy(par_strategy_by_name(name).unwrap());
+            let mut list = vec![3.0, f64::NAN, 1.0, 2.0, 1.0];
+
+            sorter.sort(&mut list);
+
+            assert_eq!(sorter.strategy().name(), name);
+            assert_eq!(&list[..4], &[1.0, 1.0, 2.0, 3.0], "{} after {}", name, previous.name());
+            assert!(list[4].is_nan());
+        }
+        assert!(par_strategy_by_name::<f64>("parallel_merge").unwrap().is_stable());
+        assert_eq!(par_strategy_by_name::<f64>("bogo").err(), Some(UnknownStrategy(String::from("bogo"))));
+    }
+
+    #[test]
+    fn sorts_on_several_threads() {
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+        use std::thread::ThreadId;
+
+        static COMPARED_ON: Mutex<Option<HashSet<ThreadId>>> = Mutex::new(None);
+
+        // Remembers which threads compared it.
+        #[derive(PartialEq)]
+        struct Probe(u32);
+
+        impl PartialOrd for Probe {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                COMPARED_ON.lock().unwrap().get_or_insert_with(HashSet::new).insert(thread::current().id());
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let mut list: Vec<Probe> = (0..2_000).rev().map(Probe).collect();
+
+        par_sort_with_strategy::<ParallelMergeSorter<4, 100>, Probe>(&mut list);
+
+        assert!(list.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(COMPARED_ON.lock().unwrap().as_ref().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn sorts_in_reverse_in_parallel() {
+        let mut list: Vec<u32> = (0..10_000).collect();
+
+        Reversed::<ParallelMergeSorter<4, 500>>::par_sort(&mut list);
+
+        assert_eq!(list, (0..10_000).rev().collect::<Vec<u32>>());
+        assert_eq!(ParallelMergeSorter::<4, 500>::NAME, "parallel_merge");
+        assert_eq!(Reversed::<ParallelMergeSorter>::NAME, "reversed_parallel_merge");
+    }
+}
@@ -0,0 +1,532 @@
+use std::fmt;
+
+/// A rule broken by a single value, before it is tied to a field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub rule: &'static str,
+    pub reason: String,
+}
+
+impl Failure {
+    pub fn new(rule: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            rule,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// A rule broken by one of the fields being validated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub field: &'static str,
+    pub rule: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.field, self.reason, self.rule)
+    }
+}
+
+/// Every violation found while validating, in the order the rules were declared.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, field: &'static str, failure: Failure) {
+        self.violations.push(Violation {
+            field,
+            rule: failure.rule,
+            reason: failure.reason,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    pub fn for_field<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a Violation> + 'a {
+        self.violations.iter().filter(move |violation| violation.field == field)
+    }
+
+    pub fn has(&self, field: &str, rule: &str) -> bool {
+        self.for_field(field).any(|violation| violation.rule == rule)
+    }
+
+    /// `Ok` when nothing was violated, so builders can use `?` on it.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, violation) in self.violations.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl IntoIterator for ValidationReport {
+    type Item = Violation;
+    type IntoIter = std::vec::IntoIter<Violation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.violations.into_iter()
+    }
+}
+
+pub trait Validator<T: ?Sized> {
+    /// Returns every rule `value` breaks, or nothing when it is valid.
+    fn validate(&self, value: &T) -> Vec<Failure>;
+}
+
+/// Runs both validators and reports the failures of each.
+pub struct And<A, B>(pub A, pub B);
+
+impl<T: ?Sized, A: Validator<T>, B: Validator<T>> Validator<T> for And<A, B> {
+    fn validate(&self, value: &T) -> Vec<Failure> {
+        let mut failures = self.0.validate(value);
+        failures.extend(self.1.validate(value));
+        failures
+    }
+}
+
+/// Requires an `Option` to be set.
+pub struct Required;
+
+impl<T> Validator<Option<T>> for Required {
+    fn validate(&self, value: &Option<T>) -> Vec<Failure> {
+        match value {
+            Some(_) => vec![],
+            None => vec![Failure::new("required", "must be set")],
+        }
+    }
+}
+
+/// Validates the value inside an `Option`, if there is one.
+pub struct Optional<V>(pub V);
+
+impl<T, V: Validator<T>> Validator<Option<T>> for Optional<V> {
+    fn validate(&self, value: &Option<T>) -> Vec<Failure> {
+        match value {
+            Some(value) => self.0.validate(value),
+            None => vec![],
+        }
+    }
+}
+
+/// Validates every item of a collection, prefixing reasons with the item's index.
+pub struct Each<V>(pub V);
+
+impl<T, V: Validator<T>> Validator<[T]> for Each<V> {
+    fn validate(&self, values: &[T]) -> Vec<Failure> {
+        values
+            .iter()
+            .enumerate()
+            .flat_map(|(index, value)| {
+                self.0.validate(value).into_iter().map(move |failure| {
+                    Failure::new(failure.rule, format!("item {} {}", index, failure.reason))
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T, V: Validator<T>> Validator<Vec<T>> for Each<V> {
+    fn validate(&self, values: &Vec<T>) -> Vec<Failure> {
+        Validator::<[T]>::validate(self, values)
+    }
+}
+
+/// Rejects empty strings and collections.
+pub struct NonEmpty;
+
+impl NonEmpty {
+    fn check(is_empty: bool) -> Vec<Failure> {
+        if is_empty {
+            vec![Failure::new("non_empty", "must not be empty")]
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl Validator<str> for NonEmpty {
+    fn validate(&self, value: &str) -> Vec<Failure> {
+        Self::check(value.is_empty())
+    }
+}
+
+impl Validator<String> for NonEmpty {
+    fn validate(&self, value: &String) -> Vec<Failure> {
+        Self::check(value.is_empty())
+    }
+}
+
+impl<T> Validator<[T]> for NonEmpty {
+    fn validate(&self, value: &[T]) -> Vec<Failure> {
+        Self::check(value.is_empty())
+    }
+}
+
+impl<T> Validator<Vec<T>> for NonEmpty {
+    fn validate(&self, value: &Vec<T>) -> Vec<Failure> {
+        Self::check(value.is_empty())
+    }
+}
+
+/// Bounds the length of a collection, or the number of characters in a string.
+pub struct Length {
+    min: Option<usize>,
+    max: Option<usize>,
+}
+
+impl Length {
+    pub fn min(min: usize) -> Self {
+        Self { min: Some(min), max: None }
+    }
+
+    pub fn max(max: usize) -> Self {
+        Self { min: None, max: Some(max) }
+    }
+
+    pub fn between(min: usize, max: usize) -> Self {
+        Self { min: Some(min), max: Some(max) }
+    }
+
+    fn check(&self, len: usize, unit: &str) -> Vec<Failure> {
+        match (self.min, self.max) {
+            (Some(min), _) if len < min => vec![Failure::new("length", format!("must have at least {} {}, got {}", min, unit, len))],
+            (_, Some(max)) if len > max => vec![Failure::new("length", format!("must have at most {} {}, got {}", max, unit, len))],
+            _ => vec![],
+        }
+    }
+}
+
+impl Validator<str> for Length {
+    fn validate(&self, value: &str) -> Vec<Failure> {
+        self.check(value.chars().count(), "characters")
+    }
+}
+
+impl Validator<String> for Length {
+    fn validate(&self, value: &String) -> Vec<Failure> {
+        self.check(value.chars().count(), "characters")
+    }
+}
+
+impl<T> Validator<[T]> for Length {
+    fn validate(&self, value: &[T]) -> Vec<Failure> {
+        self.check(value.len(), "items")
+    }
+}
+
+impl<T> Validator<Vec<T>> for Length {
+    fn validate(&self, value: &Vec<T>) -> Vec<Failure> {
+        self.check(value.len(), "items")
+    }
+}
+
+/// Accepts calendar dates written as `DD/MM/YYYY`, like `25/12/1988`.
+pub struct Date;
+
+impl Date {
+    fn check(value: &str) -> Vec<Failure> {
+        let malformed = || vec![Failure::new("date", format!("`{}` is not a DD/MM/YYYY date", value))];
+
+        let parts: Vec<&str> = value.split('/').collect();
+        let well_formed = parts.len() == 3
+            && parts.iter().zip(&[2, 2, 4]).all(|(part, &len)| {
+                part.len() == len && part.bytes().all(|byte| byte.is_ascii_digit())
+            });
+        if !well_formed {
+            return malformed();
+        }
+
+        let day: u32 = parts[0].parse().unwrap();
+        let month: u32 = parts[1].parse().unwrap();
+        let year: u32 = parts[2].parse().unwrap();
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return vec![Failure::new("date", format!("month {} does not exist", month))],
+        };
+
+        if day == 0 || day > days_in_month {
+            return vec![Failure::new("date", format!("day {} does not exist in month {} of {}", day, month, year))];
+        }
+
+        vec![]
+    }
+}
+
+impl Validator<str> for Date {
+    fn validate(&self, value: &str) -> Vec<Failure> {
+        Self::check(value)
+    }
+}
+
+impl Validator<String> for Date {
+    fn validate(&self, value: &String) -> Vec<Failure> {
+        Self::check(value)
+    }
+}
+
+/// A named rule backed by a predicate, for anything the built-in validators don't cover.
+pub struct Check<F> {
+    rule: &'static str,
+    reason: &'static str,
+    predicate: F,
+}
+
+pub fn check<T: ?Sized, F: Fn(&T) -> bool>(rule: &'static str, reason: &'static str, predicate: F) -> Check<F> {
+    Check {
+        rule,
+        reason,
+        predicate,
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T) -> bool> Validator<T> for Check<F> {
+    fn validate(&self, value: &T) -> Vec<Failure> {
+        if (self.predicate)(value) {
+            vec![]
+        } else {
+            vec![Failure::new(self.rule, self.reason)]
+        }
+    }
+}
+
+type Rule<S> = Box<dyn Fn(&S, &mut ValidationReport)>;
+
+/// The rules a builder (or any `S`) must follow, attached per field.
+pub struct Validation<S> {
+    rules: Vec<Rule<S>>,
+}
+
+impl<S: 'static> Validation<S> {
+    pub fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Validates the field `get` reads from the subject, reporting failures under `field`.
+    pub fn field<F, V>(mut self, field: &'static str, get: fn(&S) -> &F, validator: V) -> Self
+    where
+        F: ?Sized + 'static,
+        V: Validator<F> + 'static,
+    {
+        self.rules.push(Box::new(move |subject, report| {
+            for failure in validator.validate(get(subject)) {
+                report.push(field, failure);
+            }
+        }));
+        self
+    }
+
+    /// Validates the whole subject, for rules spanning several fields.
+    pub fn rule<V>(mut self, field: &'static str, validator: V) -> Self
+    where
+        V: Validator<S> + 'static,
+    {
+        self.rules.push(Box::new(move |subject, report| {
+            for failure in validator.validate(subject) {
+                report.push(field, failure);
+            }
+        }));
+        self
+    }
+
+    pub fn validate(&self, subject: &S) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        for rule in &self.rules {
+            rule(subject, &mut report);
+        }
+        report
+    }
+}
+
+impl<S: 'static> Default for Validation<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Builder;
+
+    #[derive(Debug, PartialEq)]
+    struct Person {
+        pub name: String,
+        pub birthdate: Option<String>,
+        pub favorite_things: Vec<String>,
+    }
+
+    #[derive(Debug, Default)]
+    struct PersonBuilder {
+        name: Option<String>,
+        birthdate: Option<String>,
+        favorite_things: Option<Vec<String>>,
+    }
+
+    impl PersonBuilder {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn with_name(&mut self, name: &str) -> &mut Self {
+            self.name = Some(String::from(name));
+            self
+        }
+
+        fn with_birthdate(&mut self, birthdate: &str) -> &mut Self {
+            self.birthdate = Some(String::from(birthdate));
+            self
+        }
+
+        fn with_favorite_thing(&mut self, favorite_thing: &str) -> &mut Self {
+            self.favorite_things.get_or_insert_with(Vec::new).push(String::from(favorite_thing));
+            self
+        }
+
+        fn validation() -> Validation<Self> {
+            Validation::<Self>::new()
+                .field("name", |builder| &builder.name, And(Required, Optional(NonEmpty)))
+                .field("birthdate", |builder| &builder.birthdate, Optional(Date))
+                .field(
+                    "favorite_things",
+                    |builder| &builder.favorite_things,
+                    And(Required, Optional(And(Length::between(1, 3), Each(NonEmpty)))),
+                )
+                .rule(
+                    "favorite_things",
+                    check("not_self", "must not include the person's own name", |builder: &Self| {
+                        match (&builder.name, &builder.favorite_things) {
+                            (Some(name), Some(favorite_things)) => !favorite_things.contains(name),
+                            _ => true,
+                        }
+                    }),
+                )
+        }
+    }
+
+    impl Builder<Person, ValidationReport> for PersonBuilder {
+        fn build(self) -> Result<Person, ValidationReport> {
+            let report = Self::validation().validate(&self);
+
+            match (self.name, self.favorite_things) {
+                (Some(name), Some(favorite_things)) if report.is_empty() => Ok(Person {
+                    name,
+                    birthdate: self.birthdate,
+                    favorite_things,
+                }),
+                _ => Err(report),
+            }
+        }
+    }
+
+    #[test]
+    fn builds_a_valid_person() {
+        let mut person_builder = PersonBuilder::new();
+
+        person_builder
+            .with_name("Evangivaldo")
+            .with_birthdate("29/02/1988")
+            .with_favorite_thing("Games");
+
+        let person = person_builder.build().unwrap();
+
+        assert_eq!(person.birthdate, Some(String::from("29/02/1988")));
+        assert_eq!(person.favorite_things, vec!["Games"]);
+    }
+
+    #[test]
+    fn reports_every_missing_field() {
+        let report = PersonBuilder::new().build().unwrap_err();
+
+        assert_eq!(report.violations(), &[
+            Violation { field: "name", rule: "required", reason: String::from("must be set") },
+            Violation { field: "favorite_things", rule: "required", reason: String::from("must be set") },
+        ]);
+    }
+
+    #[test]
+    fn reports_field_level_violations() {
+        let mut person_builder = PersonBuilder::new();
+
+        person_builder
+            .with_name("")
+            .with_birthdate("31/04/1988")
+            .with_favorite_thing("Games")
+            .with_favorite_thing("")
+            .with_favorite_thing("Traveling")
+            .with_favorite_thing("Music");
+
+        let report = person_builder.build().unwrap_err();
+
+        assert_eq!(report.len(), 5);
+        assert!(report.has("name", "non_empty"));
+        assert!(report.has("favorite_things", "not_self"));
+        assert!(report.has("birthdate", "date"));
+        assert!(report.has("favorite_things", "length"));
+        assert!(report.has("favorite_things", "non_empty"));
+        assert_eq!(report.for_field("favorite_things").nth(1).unwrap().reason, "item 1 must not be empty");
+    }
+
+    #[test]
+    fn reports_cross_field_violations() {
+        let mut person_builder = PersonBuilder::new();
+
+        person_builder
+            .with_name("Fulano")
+            .with_favorite_thing("Fulano");
+
+        let report = person_builder.build().unwrap_err();
+
+        assert_eq!(report.len(), 1);
+        assert!(report.has("favorite_things", "not_self"));
+        assert_eq!(report.to_string(), "favorite_things: must not include the person's own name (not_self)");
+    }
+
+    #[test]
+    fn validates_dates() {
+        assert!(Date.validate("25/12/1988").is_empty());
+        assert!(Date.validate("29/02/2000").is_empty());
+        assert!(!Date.validate("29/02/1900").is_empty());
+        assert!(!Date.validate("00/01/2000").is_empty());
+        assert!(!Date.validate("01/13/2000").is_empty());
+        assert!(!Date.validate("1/1/2000").is_empty());
+        assert!(!Date.validate("25-12-1988").is_empty());
+    }
+}